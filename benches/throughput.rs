@@ -0,0 +1,90 @@
+//! Throughput baseline: `cargo bench --bench throughput`.
+//!
+//! Every row reports MiB/s of unencoded data, the unit the `base64` crate's benchmarks use, so
+//! the numbers can be put side by side with a run of those at the same sizes. The `copy` row
+//! is the speed of copying the same bytes into a fresh `Vec`, an upper bound for any encoder
+//! that allocates its output, for normalizing results across machines.
+//!
+//! The `branchless` row decodes the same valid input with `decode_branchless`. The `_bad` rows
+//! decode it with one invalid character in the middle, where `decode` stops early and
+//! `decode_branchless` decodes everything and then again up to the error.
+//!
+//! The `radix_encode` row encodes the same alphabet, unpadded, with `Radix`. Both share one
+//! bit-packing core, so it should stay level with `encode`.
+//!
+//! Run with `--features parallel` to add an `encode_par` row. It only uses more than one
+//! thread from 1 MiB up, so the 16 MiB size is included to show how it scales with cores.
+
+extern crate base32;
+
+use base32::{Alphabet, Radix};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIZES: &[usize] = &[1, 5, 10, 16, 32, 64, 256, 1024, 16 * 1024, 1024 * 1024, 16 * 1024 * 1024];
+const TARGET: Duration = Duration::from_millis(200);
+
+/// Runs `f` repeatedly for roughly `TARGET` and prints the time per call along with the
+/// throughput over `bytes` bytes of unencoded data.
+fn bench<F: FnMut()>(name: &str, bytes: usize, mut f: F) {
+    let mut iterations = 1u64;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            f();
+        }
+        let elapsed = start.elapsed();
+        if elapsed >= TARGET {
+            let ns = elapsed.as_nanos() as f64 / iterations as f64;
+            let throughput = (bytes as u64 * iterations) as f64 / elapsed.as_secs_f64();
+            println!(
+                "{:<14} {:>8} B {:>12.1} ns/iter {:>10.1} MiB/s",
+                name,
+                bytes,
+                ns,
+                throughput / (1024.0 * 1024.0)
+            );
+            return;
+        }
+        iterations *= 2;
+    }
+}
+
+fn main() {
+    let alphabet = Alphabet::Rfc4648 { padding: true };
+    for &size in SIZES {
+        let data = (0..size).map(|i| (i * 31 + 7) as u8).collect::<Vec<u8>>();
+        let encoded = base32::encode(alphabet, &data);
+        // Invalid halfway through, where `decode` stops and `decode_branchless` has to go back.
+        let mut invalid = encoded.clone().into_bytes();
+        let middle = invalid.len() / 2;
+        invalid[middle] = b'!';
+        let invalid = String::from_utf8(invalid).unwrap();
+
+        bench("copy", size, || {
+            black_box(black_box(&data[..]).to_vec());
+        });
+        bench("encode", size, || {
+            black_box(base32::encode(alphabet, black_box(&data)));
+        });
+        bench("decode", size, || {
+            black_box(base32::decode(alphabet, black_box(&encoded)));
+        });
+        bench("branchless", size, || {
+            let _ = black_box(base32::decode_branchless(alphabet, black_box(&encoded)));
+        });
+        bench("decode_bad", size, || {
+            black_box(base32::decode(alphabet, black_box(&invalid)));
+        });
+        bench("branchless_bad", size, || {
+            let _ = black_box(base32::decode_branchless(alphabet, black_box(&invalid)));
+        });
+        bench("radix_encode", size, || {
+            black_box(Radix::BASE32.encode(black_box(&data)));
+        });
+        #[cfg(feature = "parallel")]
+        bench("encode_par", size, || {
+            black_box(base32::encode_par(alphabet, black_box(&data)));
+        });
+    }
+}
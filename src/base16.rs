@@ -0,0 +1,123 @@
+//! Conversion between base32 and hexadecimal text.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{encode, Alphabet, Config, DecodeError};
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Decodes `data` and returns the bytes as lowercase hexadecimal, for displaying a base32 value
+/// next to tools that print hex.
+///
+/// ```
+/// use base32::{base32_to_hex, Alphabet};
+///
+/// assert_eq!(base32_to_hex(Alphabet::Crockford, "CSQPY").unwrap(), "666f6f");
+/// ```
+pub fn base32_to_hex(alphabet: Alphabet, data: &str) -> Result<String, DecodeError> {
+    let bytes = Config::new(alphabet).decode(data)?;
+    let mut ret = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        ret.push(char::from(HEX_DIGITS[usize::from(b >> 4)]));
+        ret.push(char::from(HEX_DIGITS[usize::from(b & 0x0F)]));
+    }
+    Ok(ret)
+}
+
+/// Encodes the bytes written as hexadecimal in `hex`, in either case. The inverse of
+/// [`base32_to_hex`].
+///
+/// A character that is not a hex digit is reported as [`DecodeError::InvalidChar`], or
+/// [`DecodeError::NonAscii`], at its byte index in `hex`. An odd number of digits is
+/// [`DecodeError::InvalidLength`].
+///
+/// ```
+/// use base32::{hex_to_base32, Alphabet, DecodeError};
+///
+/// let alphabet = Alphabet::Rfc4648 { padding: true };
+/// assert_eq!(hex_to_base32(alphabet, "666F6F").unwrap(), "MZXW6===");
+/// assert_eq!(hex_to_base32(alphabet, "666"), Err(DecodeError::InvalidLength { length: 3 }));
+/// ```
+pub fn hex_to_base32(alphabet: Alphabet, hex: &str) -> Result<String, DecodeError> {
+    let digits = hex.as_bytes();
+    let mut bytes = Vec::with_capacity(digits.len() / 2);
+    for (i, pair) in digits.chunks(2).enumerate() {
+        let mut byte = 0;
+        for (j, &c) in pair.iter().enumerate() {
+            let index = i * 2 + j;
+            let value = match c {
+                b'0'..=b'9' => c - b'0',
+                b'a'..=b'f' => c - b'a' + 10,
+                b'A'..=b'F' => c - b'A' + 10,
+                _ if !c.is_ascii() => return Err(DecodeError::NonAscii { index }),
+                _ => return Err(DecodeError::InvalidChar { index, byte: c }),
+            };
+            byte = byte << 4 | value;
+        }
+        if pair.len() < 2 {
+            return Err(DecodeError::InvalidLength {
+                length: digits.len(),
+            });
+        }
+        bytes.push(byte);
+    }
+    Ok(encode(alphabet, &bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{Alphabet, DecodeError};
+    use super::{base32_to_hex, hex_to_base32};
+
+    #[test]
+    fn known_values() {
+        let rfc = Alphabet::Rfc4648 { padding: true };
+        let vectors = [
+            (rfc, "", ""),
+            (rfc, "MY======", "66"),
+            (rfc, "MZXW6YTBOI======", "666f6f626172"),
+            (Alphabet::Crockford, "Z0Z0Z0Z0", "f83e0f83e0"),
+            (Alphabet::Rfc4648Lower { padding: false }, "7a7h7a7h", "f83e7f83e7"),
+        ];
+        for &(alphabet, base32, hex) in &vectors {
+            assert_eq!(base32_to_hex(alphabet, base32).unwrap(), hex);
+            assert_eq!(hex_to_base32(alphabet, hex).unwrap(), base32);
+            assert_eq!(hex_to_base32(alphabet, &hex.to_uppercase()).unwrap(), base32);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        let alphabet = Alphabet::Crockford;
+        assert_eq!(
+            base32_to_hex(alphabet, "CSQPU"),
+            Err(DecodeError::InvalidChar {
+                index: 4,
+                byte: b'U'
+            })
+        );
+        assert_eq!(
+            hex_to_base32(alphabet, "66g6"),
+            Err(DecodeError::InvalidChar {
+                index: 2,
+                byte: b'g'
+            })
+        );
+        assert_eq!(
+            hex_to_base32(alphabet, "66\u{e9}"),
+            Err(DecodeError::NonAscii { index: 2 })
+        );
+        assert_eq!(
+            hex_to_base32(alphabet, "66 6"),
+            Err(DecodeError::InvalidChar {
+                index: 2,
+                byte: b' '
+            })
+        );
+        assert_eq!(
+            hex_to_base32(alphabet, "6"),
+            Err(DecodeError::InvalidLength { length: 1 })
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Adaptors for the `bytes` crate's `Buf` and `BufMut`.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use bytes::{Buf, BufMut};
+
+use super::{ascii_string, Alphabet, Config, DecodeError, DecodeState, INPUT_CHUNK};
+
+/// Encodes the remaining bytes of `input` like [`encode`](super::encode), consuming them. The
+/// input may be split across any number of chunks, such as a chain of `Bytes` frames; a group
+/// of 5 bytes straddling two chunks is put back together without copying the rest of the
+/// input. Requires the `bytes` feature.
+///
+/// ```
+/// # extern crate base32;
+/// # extern crate bytes;
+/// use base32::{encode_buf, Alphabet};
+/// use bytes::{Buf, Bytes};
+///
+/// let input = Bytes::from_static(b"foo").chain(Bytes::from_static(b"bar"));
+/// assert_eq!(encode_buf(Alphabet::Crockford, input), "CSQPYRK1E8");
+/// ```
+pub fn encode_buf<B: Buf>(alphabet: Alphabet, mut input: B) -> String {
+    let config = Config::new(alphabet);
+    let mut ret = Vec::with_capacity(config.encoded_len(input.remaining()));
+    let mut pending = [0u8; INPUT_CHUNK];
+    let mut pending_len = 0;
+    while input.has_remaining() {
+        let mut chunk = input.chunk();
+        let n = chunk.len();
+        if pending_len > 0 {
+            let m = (INPUT_CHUNK - pending_len).min(chunk.len());
+            pending[pending_len..pending_len + m].copy_from_slice(&chunk[..m]);
+            pending_len += m;
+            chunk = &chunk[m..];
+            if pending_len < INPUT_CHUNK {
+                input.advance(n);
+                continue;
+            }
+            config.encode_into(&pending, &mut ret);
+        }
+        let (groups, rest) = chunk.split_at(chunk.len() / INPUT_CHUNK * INPUT_CHUNK);
+        config.encode_into(groups, &mut ret);
+        pending[..rest.len()].copy_from_slice(rest);
+        pending_len = rest.len();
+        input.advance(n);
+    }
+    config.encode_into(&pending[..pending_len], &mut ret);
+    ascii_string(ret)
+}
+
+/// Decodes the remaining text of `input` like [`decode`](super::decode), consuming it and
+/// putting the bytes into `output`. The text may be split across chunks anywhere, even inside a
+/// group, and error indices count from the start of `input`. Requires the `bytes` feature.
+///
+/// On error `output` holds the bytes of the groups before the invalid one. As with
+/// [`DecodeState`], [`DecodeError::InvalidLength`] reports the length of the last group.
+///
+/// ```
+/// # extern crate base32;
+/// # extern crate bytes;
+/// use base32::{decode_buf, Alphabet};
+/// use bytes::{Buf, Bytes, BytesMut};
+///
+/// let input = Bytes::from_static(b"CSQP").chain(Bytes::from_static(b"YRK1E8"));
+/// let mut output = BytesMut::new();
+/// decode_buf(Alphabet::Crockford, input, &mut output).unwrap();
+/// assert_eq!(output, "foobar");
+/// ```
+pub fn decode_buf<B: Buf, M: BufMut>(
+    alphabet: Alphabet,
+    mut input: B,
+    output: &mut M,
+) -> Result<(), DecodeError> {
+    let mut state = DecodeState::new(alphabet);
+    let mut decoded = Vec::new();
+    while input.has_remaining() {
+        let chunk = input.chunk();
+        let n = chunk.len();
+        let result = state.push_into(chunk, &mut decoded);
+        output.put_slice(&decoded);
+        decoded.clear();
+        result?;
+        input.advance(n);
+    }
+    output.put_slice(&state.finish()?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::Alphabet::{Crockford, Rfc4648};
+    use super::super::{encode, DecodeError};
+    use super::{decode_buf, encode_buf};
+    use alloc::vec::Vec;
+    use bytes::{Buf, Bytes, BytesMut};
+
+    /// Splits `data` into three `Bytes` frames at `a` and `b`, chained into one `Buf`.
+    fn fragments(data: &[u8], a: usize, b: usize) -> impl Buf {
+        let a = a % (data.len() + 1);
+        let b = a + b % (data.len() - a + 1);
+        let data = Bytes::copy_from_slice(data);
+        data.slice(..a)
+            .chain(data.slice(a..b))
+            .chain(data.slice(b..))
+    }
+
+    #[test]
+    fn encode_fragmented() {
+        fn test(data: Vec<u8>, a: usize, b: usize, padding: bool) -> bool {
+            let alphabet = Rfc4648 { padding };
+            encode_buf(alphabet, fragments(&data, a, b)) == encode(alphabet, &data)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize, usize, bool) -> bool);
+        // Frames shorter than a group.
+        let input = Bytes::from_static(b"fo")
+            .chain(Bytes::from_static(b"o"))
+            .chain(Bytes::from_static(b"ba"))
+            .chain(Bytes::from_static(b"r"));
+        assert_eq!(encode_buf(Crockford, input), "CSQPYRK1E8");
+        assert_eq!(encode_buf(Crockford, Bytes::new()), "");
+    }
+
+    #[test]
+    fn decode_fragmented() {
+        fn test(data: Vec<u8>, a: usize, b: usize, padding: bool) -> bool {
+            let alphabet = Rfc4648 { padding };
+            let encoded = encode(alphabet, &data);
+            let mut output = BytesMut::new();
+            decode_buf(alphabet, fragments(encoded.as_bytes(), a, b), &mut output).is_ok()
+                && output == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize, usize, bool) -> bool);
+        // Padding split from its group.
+        let input = Bytes::from_static(b"MZXW6=").chain(Bytes::from_static(b"=="));
+        let mut output = Vec::new();
+        decode_buf(Rfc4648 { padding: true }, input, &mut output).unwrap();
+        assert_eq!(output, b"foo");
+    }
+
+    #[test]
+    fn decode_errors() {
+        // The index counts from the start of the input, across frames.
+        let input = Bytes::from_static(b"CSQPY").chain(Bytes::from_static(b"RK1EU"));
+        let mut output = Vec::new();
+        assert_eq!(
+            decode_buf(Crockford, input, &mut output),
+            Err(DecodeError::InvalidChar {
+                index: 9,
+                byte: b'U'
+            })
+        );
+        assert_eq!(output, b"fooba");
+        // As with `DecodeState`, a bad length is that of the last group.
+        let input = Bytes::from_static(b"MZXW6").chain(Bytes::from_static(b"YTBO"));
+        let mut output = Vec::new();
+        assert_eq!(
+            decode_buf(Rfc4648 { padding: false }, input, &mut output),
+            Err(DecodeError::InvalidLength { length: 1 })
+        );
+    }
+}
@@ -0,0 +1,703 @@
+//! Helpers specific to Crockford's Base32.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{
+    ascii_string, decode, encode, unpadded_len, Alphabet, Case, Config, DecodeError, CROCKFORD,
+};
+
+/// Encodes `data` as a big-endian unsigned number in Crockford's Base32, with leading `0`
+/// symbols removed (but at least one symbol kept).
+///
+/// This is the numeric interpretation of the bytes, not `encode(Alphabet::Crockford, data)`
+/// with its leading zeros stripped: [`encode`](super::encode) aligns the bits to the start of
+/// the first symbol and pads the last one, while this function aligns them to the end of the
+/// last symbol, like the digits of a number. The two agree only when `data.len()` is a multiple
+/// of 5. Decode the result with [`decode_crockford_trim`], not [`decode`](super::decode).
+///
+/// ```
+/// use base32::encode_crockford_trim;
+///
+/// assert_eq!(encode_crockford_trim(&[0x00, 0x00, 0x01]), "1");
+/// assert_eq!(encode_crockford_trim(&[0x01, 0x00]), "80");
+/// assert_eq!(encode_crockford_trim(&[]), "0");
+/// ```
+pub fn encode_crockford_trim(data: &[u8]) -> String {
+    let symbols = CROCKFORD.symbols();
+    let mut ret = Vec::with_capacity(unpadded_len(data.len()));
+    let mut bits = 0u16;
+    let mut len = 0;
+    for &b in data.iter().rev() {
+        bits |= (b as u16) << len;
+        len += 8;
+        while len >= 5 {
+            ret.push(symbols[(bits & 0x1F) as usize]);
+            bits >>= 5;
+            len -= 5;
+        }
+    }
+    if len > 0 {
+        ret.push(symbols[bits as usize]);
+    }
+    while ret.len() > 1 && ret.last() == Some(&b'0') {
+        ret.pop();
+    }
+    if ret.is_empty() {
+        ret.push(b'0');
+    }
+    ret.reverse();
+    ascii_string(ret)
+}
+
+/// Decodes the output of [`encode_crockford_trim`] into exactly `len` big-endian bytes,
+/// restoring the leading zero bytes that the numeric form drops.
+///
+/// Returns `None` if `data` is empty, contains a character outside Crockford's alphabet, or
+/// represents a number that does not fit in `len` bytes. Decoding is case-insensitive and
+/// accepts `I`, `L` and `O` like [`decode`](super::decode).
+///
+/// ```
+/// use base32::decode_crockford_trim;
+///
+/// assert_eq!(decode_crockford_trim("1", 3).unwrap(), [0x00, 0x00, 0x01]);
+/// assert_eq!(decode_crockford_trim("80", 2).unwrap(), [0x01, 0x00]);
+/// assert_eq!(decode_crockford_trim("80", 1), None);
+/// ```
+#[must_use]
+pub fn decode_crockford_trim(data: &str, len: usize) -> Option<Vec<u8>> {
+    if data.is_empty() {
+        return None;
+    }
+    let values = CROCKFORD.values();
+    let mut ret = Vec::with_capacity(len);
+    let mut bits = 0u16;
+    let mut bits_len = 0;
+    for &c in data.as_bytes().iter().rev() {
+        let value = values[c as usize];
+        if value == -1 {
+            return None;
+        }
+        bits |= (value as u16) << bits_len;
+        bits_len += 5;
+        if bits_len >= 8 {
+            ret.push(bits as u8);
+            bits >>= 8;
+            bits_len -= 8;
+        }
+    }
+    if bits != 0 {
+        ret.push(bits as u8);
+    }
+    while ret.len() > len {
+        if ret.pop() != Some(0) {
+            return None;
+        }
+    }
+    ret.resize(len, 0);
+    ret.reverse();
+    Some(ret)
+}
+
+/// Encodes `n` in Crockford's Base32 with as few symbols as possible, so up to 26 for
+/// `u128::MAX`. The same as [`encode_crockford_trim`] of `n.to_be_bytes()`.
+///
+/// ```
+/// use base32::encode_u128_crockford;
+///
+/// assert_eq!(encode_u128_crockford(0), "0");
+/// assert_eq!(encode_u128_crockford(1234), "16J");
+/// assert_eq!(encode_u128_crockford(u128::MAX), "7ZZZZZZZZZZZZZZZZZZZZZZZZZ");
+/// ```
+pub fn encode_u128_crockford(mut n: u128) -> String {
+    let symbols = CROCKFORD.symbols();
+    let mut ret = Vec::with_capacity(26);
+    loop {
+        ret.push(symbols[(n & 0x1F) as usize]);
+        n >>= 5;
+        if n == 0 {
+            break;
+        }
+    }
+    ret.reverse();
+    ascii_string(ret)
+}
+
+/// Decodes a number written by [`encode_u128_crockford`]. Leading `0` symbols are allowed.
+///
+/// Returns `None` if `data` is empty, contains a character outside Crockford's alphabet, or
+/// represents a number greater than `u128::MAX`. Decoding is case-insensitive and accepts `I`,
+/// `L` and `O` like [`decode`](super::decode).
+///
+/// ```
+/// use base32::decode_u128_crockford;
+///
+/// assert_eq!(decode_u128_crockford("16j"), Some(1234));
+/// assert_eq!(decode_u128_crockford("7ZZZZZZZZZZZZZZZZZZZZZZZZZ"), Some(u128::MAX));
+/// assert_eq!(decode_u128_crockford("80000000000000000000000000"), None);
+/// ```
+#[must_use]
+pub fn decode_u128_crockford(data: &str) -> Option<u128> {
+    if data.is_empty() {
+        return None;
+    }
+    let values = CROCKFORD.values();
+    data.bytes().try_fold(0u128, |n, c| {
+        let value = values[c as usize];
+        if value == -1 || n.leading_zeros() < 5 {
+            return None;
+        }
+        Some(n << 5 | value as u128)
+    })
+}
+
+/// Encodes `n` in Crockford's Base32 as exactly `width` symbols, adding leading `0` symbols as
+/// needed, or returns `None` if `n` needs more than `width` symbols. Every `u64` fits in 13.
+///
+/// Numbers written with the same width compare as strings the way they compare as numbers,
+/// which makes this suitable for sortable keys and IDs. Decode the result with
+/// [`decode_u64_crockford_fixed`].
+///
+/// ```
+/// use base32::encode_u64_crockford_fixed;
+///
+/// assert_eq!(encode_u64_crockford_fixed(1234, 6).unwrap(), "00016J");
+/// assert_eq!(encode_u64_crockford_fixed(u64::MAX, 13).unwrap(), "FZZZZZZZZZZZZ");
+/// assert_eq!(encode_u64_crockford_fixed(1234, 2), None);
+/// ```
+pub fn encode_u64_crockford_fixed(n: u64, width: usize) -> Option<String> {
+    let digits = encode_u128_crockford(n as u128);
+    if digits.len() > width {
+        return None;
+    }
+    let mut ret = String::with_capacity(width);
+    ret.extend((digits.len()..width).map(|_| '0'));
+    ret.push_str(&digits);
+    Some(ret)
+}
+
+/// Decodes a number written by [`encode_u64_crockford_fixed`] with the same `width`, ignoring
+/// the leading `0` symbols.
+///
+/// Returns `None` if `data` is not exactly `width` symbols long, contains a character outside
+/// Crockford's alphabet, or represents a number greater than `u64::MAX`. Decoding is
+/// case-insensitive and accepts `I`, `L` and `O` like [`decode`](super::decode).
+///
+/// ```
+/// use base32::decode_u64_crockford_fixed;
+///
+/// assert_eq!(decode_u64_crockford_fixed("00016j", 6), Some(1234));
+/// assert_eq!(decode_u64_crockford_fixed("16J", 6), None);
+/// assert_eq!(decode_u64_crockford_fixed("G000000000000", 13), None);
+/// ```
+#[must_use]
+pub fn decode_u64_crockford_fixed(data: &str, width: usize) -> Option<u64> {
+    if data.len() != width {
+        return None;
+    }
+    match decode_u128_crockford(data)? {
+        n if n > u64::MAX as u128 => None,
+        n => Some(n as u64),
+    }
+}
+
+/// The 37 check symbols: the 32 Crockford symbols followed by the 5 extra symbols only used
+/// for checking.
+const CHECK_SYMBOLS: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
+/// Returns Crockford's check symbol for `data` read as a big-endian unsigned number: the
+/// number modulo 37, written with one of 37 symbols, the extra five being `*~$=U`.
+///
+/// Only the check symbol is computed, without encoding the data, for systems that store it
+/// separately from the payload.
+///
+/// ```
+/// use base32::crockford_check_symbol;
+///
+/// // 1234 is "16J" in Crockford's Base32, and 1234 mod 37 = 13.
+/// assert_eq!(crockford_check_symbol(&[0x04, 0xD2]), 'D');
+/// ```
+pub fn crockford_check_symbol(data: &[u8]) -> char {
+    let value = data
+        .iter()
+        .fold(0u32, |value, &b| (value * 256 + b as u32) % 37);
+    CHECK_SYMBOLS[value as usize] as char
+}
+
+/// Formats `data` as a product key: its Crockford encoding split into hyphenated groups of
+/// `group` symbols, followed by a hyphen and the [check symbol](crockford_check_symbol) of
+/// `data`. Decode it with [`decode_license_key`].
+///
+/// # Panics
+///
+/// Panics if `group` is 0.
+///
+/// ```
+/// use base32::encode_license_key;
+///
+/// assert_eq!(encode_license_key(b"foobar", 4), "CSQP-YRK1-E8-6");
+/// ```
+pub fn encode_license_key(data: &[u8], group: usize) -> String {
+    encode_license_key_cased(data, group, Case::Upper, Case::Upper)
+}
+
+/// Like [`encode_license_key`], but with the case of the data symbols and of the check symbol
+/// chosen independently, to match existing formats exactly:
+///
+/// | `symbols` | `check` | `[0x04, 0xD2]`, groups of 2 |
+/// |-----------|---------|-----------------------------|
+/// | `Upper`   | `Upper` | `0K-90-D`                   |
+/// | `Lower`   | `Lower` | `0k-90-d`                   |
+/// | `Upper`   | `Lower` | `0K-90-d`                   |
+/// | `Lower`   | `Upper` | `0k-90-D`                   |
+///
+/// The hyphens and the extra check symbols `*~$=` have no case. [`decode_license_key`]
+/// accepts every combination.
+///
+/// # Panics
+///
+/// Panics if `group` is 0.
+///
+/// ```
+/// use base32::{encode_license_key_cased, Case};
+///
+/// // 1234 mod 37 = 13, so the check symbol is `D`.
+/// let key = encode_license_key_cased(&[0x04, 0xD2], 2, Case::Lower, Case::Upper);
+/// assert_eq!(key, "0k-90-D");
+/// ```
+pub fn encode_license_key_cased(data: &[u8], group: usize, symbols: Case, check: Case) -> String {
+    assert!(group > 0, "group size must be non-zero");
+    let mut encoded = encode(Alphabet::Crockford, data);
+    if symbols == Case::Lower {
+        encoded.make_ascii_lowercase();
+    }
+    let mut ret = String::with_capacity(encoded.len() + encoded.len() / group + 2);
+    for chunk in encoded.as_bytes().chunks(group) {
+        ret.extend(chunk.iter().map(|&c| c as char));
+        ret.push('-');
+    }
+    let check_symbol = crockford_check_symbol(data);
+    ret.push(match check {
+        Case::Upper => check_symbol,
+        Case::Lower => check_symbol.to_ascii_lowercase(),
+    });
+    ret
+}
+
+/// Decodes a product key made by [`encode_license_key`], returning `None` if it is not valid
+/// Crockford's Base32 or the check symbol does not match.
+///
+/// Hyphens are ignored wherever they are, so the group size does not need to be known, and
+/// like [`decode`](super::decode) the key is case-insensitive. The check symbol is required
+/// even for empty data, whose key is `"0"`: a key of only hyphens, such as `"----"`, has no
+/// check symbol and is rejected, but `"---0"` decodes to an empty `Vec`.
+///
+/// ```
+/// use base32::decode_license_key;
+///
+/// assert_eq!(decode_license_key("CSQP-YRK1-E8-6").unwrap(), b"foobar");
+/// assert_eq!(decode_license_key("csqp-yrk1-e8-6").unwrap(), b"foobar");
+/// assert_eq!(decode_license_key("CSQP-YRK2-E8-6"), None);
+/// ```
+#[must_use]
+pub fn decode_license_key(key: &str) -> Option<Vec<u8>> {
+    let (body, check) = match key.rfind('-') {
+        Some(i) => (&key[..i], &key[i + 1..]),
+        None => ("", key),
+    };
+    let mut check = check.chars();
+    let check = match (check.next(), check.next()) {
+        (Some(c), None) => c.to_ascii_uppercase(),
+        _ => return None,
+    };
+    let body = body.chars().filter(|&c| c != '-').collect::<String>();
+    let data = decode(Alphabet::Crockford, &body)?;
+    if crockford_check_symbol(&data) != check {
+        return None;
+    }
+    Some(data)
+}
+
+/// Rewrites hyphenated Crockford's Base32, as typed into a code-entry field, in canonical form:
+/// the existing hyphens are removed, every symbol is replaced by its uppercase form, with `I`
+/// and `L` read as `1` and `O` as `0`, and a hyphen is put after every `group` symbols.
+///
+/// Returns `None` if the text without hyphens does not [`decode`](super::decode). The symbols
+/// themselves are kept, so the result has as many of them as the input, and text of only
+/// hyphens becomes the empty string.
+///
+/// # Panics
+///
+/// Panics if `group` is 0.
+///
+/// ```
+/// use base32::regroup_crockford;
+///
+/// assert_eq!(regroup_crockford("csq-pyrk-ie8", 4).unwrap(), "CSQP-YRK1-E8");
+/// assert_eq!(regroup_crockford("CSQP-YRKU-E8", 4), None);
+/// ```
+pub fn regroup_crockford(data: &str, group: usize) -> Option<String> {
+    assert!(group > 0, "group size must be non-zero");
+    let body = data.chars().filter(|&c| c != '-').collect::<String>();
+    decode(Alphabet::Crockford, &body)?;
+    let symbols = CROCKFORD.symbols();
+    let values = CROCKFORD.values();
+    let mut ret = Vec::with_capacity(body.len() + body.len() / group);
+    for (i, chunk) in body.as_bytes().chunks(group).enumerate() {
+        if i > 0 {
+            ret.push(b'-');
+        }
+        ret.extend(chunk.iter().map(|&c| symbols[values[c as usize] as usize]));
+    }
+    Some(ascii_string(ret))
+}
+
+/// The check symbol of a group of symbol values: the group read as a big-endian base-32
+/// number, modulo 37.
+fn group_check(values: impl Iterator<Item = u8>) -> u8 {
+    let value = values.fold(0u32, |value, v| (value * 32 + v as u32) % 37);
+    CHECK_SYMBOLS[value as usize]
+}
+
+/// Encodes `data` in Crockford's Base32 with a check symbol after every `group` symbols, so a
+/// transcription error can be pinned to the group it is in. Decode it with
+/// [`decode_crockford_interleaved_check`].
+///
+/// The encoding of `data` is split into groups of `group` symbols, the last one possibly
+/// shorter, and each group is followed by its check: the group's symbols read as a big-endian
+/// base-32 number, modulo 37, written with the same 37 symbols as
+/// [`crockford_check_symbol`]. Each check covers only its own group, not the data before it.
+/// There are no separators; the output is `n + ceil(n / group)` characters for `n` symbols.
+///
+/// # Panics
+///
+/// Panics if `group` is 0.
+///
+/// ```
+/// use base32::encode_crockford_interleaved_check;
+///
+/// // "0K90" is 19744, and 19744 mod 37 = 23, so the check symbol is `Q`.
+/// assert_eq!(encode_crockford_interleaved_check(&[0x04, 0xD2], 4), "0K90Q");
+/// assert_eq!(encode_crockford_interleaved_check(b"foobar", 4), "CSQPZYRK1CE8C");
+/// ```
+pub fn encode_crockford_interleaved_check(data: &[u8], group: usize) -> String {
+    assert!(group > 0, "group size must be non-zero");
+    let encoded = encode(Alphabet::Crockford, data);
+    let values = CROCKFORD.values();
+    let mut ret = Vec::with_capacity(encoded.len() + encoded.len().div_ceil(group));
+    for chunk in encoded.as_bytes().chunks(group) {
+        ret.extend_from_slice(chunk);
+        ret.push(group_check(chunk.iter().map(|&c| values[c as usize] as u8)));
+    }
+    ascii_string(ret)
+}
+
+/// Decodes the output of [`encode_crockford_interleaved_check`] made with the same `group`.
+///
+/// Every group's check symbol is verified before the data is decoded, and the first group
+/// whose check does not match is reported as [`DecodeError::CheckMismatch`], counting from 0.
+/// Symbols outside Crockford's alphabet are reported at their index in `data`, and a final
+/// group with no symbols before its check as [`DecodeError::InvalidLength`]. Like
+/// [`decode`](super::decode), decoding is case-insensitive and accepts `I`, `L` and `O`.
+///
+/// # Panics
+///
+/// Panics if `group` is 0.
+///
+/// ```
+/// use base32::{decode_crockford_interleaved_check, DecodeError};
+///
+/// assert_eq!(decode_crockford_interleaved_check("CSQPZYRK1CE8C", 4).unwrap(), b"foobar");
+/// assert_eq!(
+///     decode_crockford_interleaved_check("CSQPZYRK2CE8C", 4),
+///     Err(DecodeError::CheckMismatch { group: 1 })
+/// );
+/// ```
+pub fn decode_crockford_interleaved_check(
+    data: &str,
+    group: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    assert!(group > 0, "group size must be non-zero");
+    let data = data.as_bytes();
+    let values = CROCKFORD.values();
+    let mut symbols = Vec::with_capacity(data.len());
+    // A group larger than `data` leaves a single chunk, so `i * stride` never overflows.
+    let stride = group.saturating_add(1);
+    for (i, chunk) in data.chunks(stride).enumerate() {
+        let start = i * stride;
+        let mut group_values = Vec::with_capacity(chunk.len());
+        for (offset, &c) in chunk.iter().enumerate() {
+            let index = start + offset;
+            if !c.is_ascii() {
+                return Err(DecodeError::NonAscii { index });
+            }
+            if offset == chunk.len() - 1 {
+                if chunk.len() == 1 {
+                    return Err(DecodeError::InvalidLength { length: data.len() });
+                }
+                let check = c.to_ascii_uppercase();
+                if !CHECK_SYMBOLS.contains(&check) {
+                    return Err(DecodeError::InvalidChar { index, byte: c });
+                }
+                if group_check(group_values.iter().copied()) != check {
+                    return Err(DecodeError::CheckMismatch { group: i });
+                }
+            } else {
+                match values[c as usize] {
+                    -1 => return Err(DecodeError::InvalidChar { index, byte: c }),
+                    value => group_values.push(value as u8),
+                }
+            }
+        }
+        symbols.extend_from_slice(&chunk[..chunk.len() - 1]);
+    }
+    Config::new(Alphabet::Crockford)
+        .decode_bytes(&symbols)
+        .map(|decoded| decoded.data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::Case;
+    use super::super::DecodeError;
+    use super::{
+        crockford_check_symbol, decode_crockford_interleaved_check, decode_crockford_trim,
+        decode_license_key, decode_u128_crockford, decode_u64_crockford_fixed,
+        encode_crockford_interleaved_check, encode_crockford_trim, encode_license_key,
+        encode_license_key_cased, encode_u128_crockford, encode_u64_crockford_fixed,
+        regroup_crockford,
+    };
+    use alloc::vec::Vec;
+
+    #[test]
+    fn trim_keeps_one_symbol() {
+        assert_eq!(encode_crockford_trim(&[]), "0");
+        assert_eq!(encode_crockford_trim(&[0]), "0");
+        assert_eq!(encode_crockford_trim(&[0; 7]), "0");
+        assert_eq!(decode_crockford_trim("0", 0).unwrap(), []);
+        assert_eq!(decode_crockford_trim("0", 2).unwrap(), [0, 0]);
+    }
+
+    #[test]
+    fn trim_is_numeric() {
+        assert_eq!(encode_crockford_trim(&[0x00, 0x20]), "10");
+        assert_eq!(encode_crockford_trim(&[0xFF]), "7Z");
+        assert_eq!(encode_crockford_trim(&[0x04, 0xD2]), "16J");
+        assert_eq!(decode_crockford_trim("16j", 4).unwrap(), [0, 0, 0x04, 0xD2]);
+        assert_eq!(decode_crockford_trim("I6J", 2).unwrap(), [0x04, 0xD2]);
+    }
+
+    #[test]
+    fn trim_invalid() {
+        assert_eq!(decode_crockford_trim("", 1), None);
+        assert_eq!(decode_crockford_trim("U", 1), None);
+        assert_eq!(decode_crockford_trim("800", 1), None);
+        assert_eq!(decode_crockford_trim("7Z", 1).unwrap(), [0xFF]);
+    }
+
+    #[test]
+    fn u128_round_trip() {
+        fn test(n: u128) -> bool {
+            let encoded = encode_u128_crockford(n);
+            encoded == encode_crockford_trim(&n.to_be_bytes())
+                && decode_u128_crockford(&encoded) == Some(n)
+        }
+        quickcheck::quickcheck(test as fn(u128) -> bool);
+
+        assert_eq!(decode_u128_crockford("000016J"), Some(1234));
+        assert_eq!(decode_u128_crockford("1O"), Some(32));
+        assert_eq!(decode_u128_crockford("16U"), None);
+        assert_eq!(decode_u128_crockford(""), None);
+        assert_eq!(
+            decode_u128_crockford("07ZZZZZZZZZZZZZZZZZZZZZZZZZ"),
+            Some(u128::MAX)
+        );
+        assert_eq!(decode_u128_crockford("ZZZZZZZZZZZZZZZZZZZZZZZZZZ"), None);
+    }
+
+    #[test]
+    fn u64_fixed() {
+        assert_eq!(encode_u64_crockford_fixed(0, 1).unwrap(), "0");
+        assert_eq!(encode_u64_crockford_fixed(0, 13).unwrap(), "0000000000000");
+        assert_eq!(encode_u64_crockford_fixed(31, 2).unwrap(), "0Z");
+        assert_eq!(encode_u64_crockford_fixed(32, 2).unwrap(), "10");
+        assert_eq!(encode_u64_crockford_fixed(u64::MAX, 20).unwrap(), "0000000FZZZZZZZZZZZZ");
+        assert_eq!(decode_u64_crockford_fixed("0000000000000", 13), Some(0));
+        assert_eq!(decode_u64_crockford_fixed("0000000FZZZZZZZZZZZZ", 20), Some(u64::MAX));
+        assert_eq!(decode_u64_crockford_fixed("0O1", 3), Some(1));
+    }
+
+    #[test]
+    fn u64_fixed_too_narrow() {
+        assert_eq!(encode_u64_crockford_fixed(0, 0), None);
+        assert_eq!(encode_u64_crockford_fixed(32, 1), None);
+        assert_eq!(encode_u64_crockford_fixed(1 << 60, 12), None);
+        assert_eq!(encode_u64_crockford_fixed(u64::MAX, 12), None);
+        assert_eq!(decode_u64_crockford_fixed("", 0), None);
+        assert_eq!(decode_u64_crockford_fixed("10", 1), None);
+        assert_eq!(decode_u64_crockford_fixed("10", 3), None);
+        assert_eq!(decode_u64_crockford_fixed("0U", 2), None);
+        // One more than u64::MAX, and the largest 13-symbol number.
+        assert_eq!(decode_u64_crockford_fixed("G000000000000", 13), None);
+        assert_eq!(decode_u64_crockford_fixed("ZZZZZZZZZZZZZ", 13), None);
+    }
+
+    #[test]
+    fn u64_fixed_sorts() {
+        fn test(a: u64, b: u64) -> bool {
+            let x = encode_u64_crockford_fixed(a, 13).unwrap();
+            let y = encode_u64_crockford_fixed(b, 13).unwrap();
+            x.cmp(&y) == a.cmp(&b) && decode_u64_crockford_fixed(&x, 13) == Some(a)
+        }
+        quickcheck::quickcheck(test as fn(u64, u64) -> bool);
+    }
+
+    #[test]
+    fn check_symbol() {
+        assert_eq!(crockford_check_symbol(&[]), '0');
+        assert_eq!(crockford_check_symbol(&[0x04, 0xD2]), 'D');
+        assert_eq!(crockford_check_symbol(&[0, 0, 0x04, 0xD2]), 'D');
+        assert_eq!(crockford_check_symbol(&[32]), '*');
+        assert_eq!(crockford_check_symbol(&[36]), 'U');
+        assert_eq!(crockford_check_symbol(&[37]), '0');
+        // 2^64 - 1 = 18446744073709551615, which is 11 mod 37.
+        assert_eq!(crockford_check_symbol(&[0xFF; 8]), 'B');
+    }
+
+    #[test]
+    fn license_key() {
+        assert_eq!(encode_license_key(b"foobar", 4), "CSQP-YRK1-E8-6");
+        assert_eq!(encode_license_key(b"foobar", 5), "CSQPY-RK1E8-6");
+        assert_eq!(encode_license_key(b"foobar", 20), "CSQPYRK1E8-6");
+        assert_eq!(encode_license_key(b"", 4), "0");
+        assert_eq!(decode_license_key("0").unwrap(), []);
+        assert_eq!(decode_license_key("CSQPY-RK1E8-6").unwrap(), b"foobar");
+        assert_eq!(decode_license_key("CSQ-PYRK1E-8-6").unwrap(), b"foobar");
+        assert_eq!(decode_license_key("CSQP-YRK1-E8-7"), None);
+        assert_eq!(decode_license_key("CSQP-YRK1-E8-"), None);
+        assert_eq!(decode_license_key("CSQP-YRK1-E86"), None);
+        assert_eq!(decode_license_key(""), None);
+    }
+
+    #[test]
+    fn license_key_case() {
+        let data = [0x04, 0xD2];
+        let keys = [
+            (Case::Upper, Case::Upper, "0K-90-D"),
+            (Case::Lower, Case::Lower, "0k-90-d"),
+            (Case::Upper, Case::Lower, "0K-90-d"),
+            (Case::Lower, Case::Upper, "0k-90-D"),
+        ];
+        for &(symbols, check, key) in &keys {
+            assert_eq!(encode_license_key_cased(&data, 2, symbols, check), key);
+            assert_eq!(decode_license_key(key).unwrap(), data);
+        }
+        assert_eq!(
+            encode_license_key_cased(b"foobar", 4, Case::Lower, Case::Upper),
+            "csqp-yrk1-e8-6"
+        );
+    }
+
+    #[test]
+    fn invertible_license_key() {
+        fn test(data: Vec<u8>, group: usize) -> bool {
+            let key = encode_license_key(&data, group % 8 + 1);
+            decode_license_key(&key).unwrap() == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize) -> bool)
+    }
+
+    #[test]
+    fn regroup() {
+        assert_eq!(regroup_crockford("", 4).unwrap(), "");
+        assert_eq!(regroup_crockford("---", 4).unwrap(), "");
+        assert_eq!(regroup_crockford("CSQPYRK1E8", 4).unwrap(), "CSQP-YRK1-E8");
+        assert_eq!(regroup_crockford("CSQPY-RK1E8", 3).unwrap(), "CSQ-PYR-K1E-8");
+        assert_eq!(regroup_crockford("c-s-q-p-y-r-k-l-e-8", 5).unwrap(), "CSQPY-RK1E8");
+        assert_eq!(regroup_crockford("oO-Il", 2).unwrap(), "00-11");
+        assert_eq!(regroup_crockford("CSQPYRK1E8", 20).unwrap(), "CSQPYRK1E8");
+        assert_eq!(regroup_crockford("CSQP YRK1", 4), None);
+        assert_eq!(regroup_crockford("CSQP-YRKU", 4), None);
+        assert_eq!(regroup_crockford("CSQP-YRK1-Ë8", 4), None);
+
+        fn test(data: Vec<u8>, group: usize) -> bool {
+            if data.is_empty() {
+                return true;
+            }
+            let group = group % 8 + 1;
+            let key = encode_license_key(&data, group);
+            let body = &key[..key.len() - 2];
+            regroup_crockford(&body.to_ascii_lowercase(), group).as_deref() == Some(body)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize) -> bool)
+    }
+
+    #[test]
+    fn interleaved_check() {
+        assert_eq!(encode_crockford_interleaved_check(b"", 4), "");
+        assert_eq!(decode_crockford_interleaved_check("", 4).unwrap(), []);
+        assert_eq!(encode_crockford_interleaved_check(b"foobar", 4), "CSQPZYRK1CE8C");
+        assert_eq!(encode_crockford_interleaved_check(b"foobar", 20), "CSQPYRK1E8R");
+        assert_eq!(
+            decode_crockford_interleaved_check("csqpzyrk1ce8c", 4).unwrap(),
+            b"foobar"
+        );
+        // A group larger than the data is one group with one check.
+        assert_eq!(encode_crockford_interleaved_check(b"foo", usize::MAX), "CSQPYQ");
+        assert_eq!(
+            decode_crockford_interleaved_check("CSQPYQ", usize::MAX).unwrap(),
+            b"foo"
+        );
+        // 32 mod 37 = 32, which needs one of the extra check symbols.
+        assert_eq!(encode_crockford_interleaved_check(&[0x08, 0x00], 2), "10*000");
+        assert_eq!(
+            decode_crockford_interleaved_check("10*000", 2).unwrap(),
+            [0x08, 0x00]
+        );
+    }
+
+    #[test]
+    fn interleaved_check_errors() {
+        for (data, error) in [
+            ("CSQPTYRK1CE8C", DecodeError::CheckMismatch { group: 0 }),
+            ("CSQPZYRK1CE8K", DecodeError::CheckMismatch { group: 2 }),
+            ("CSQPZYRK1CE9C", DecodeError::CheckMismatch { group: 2 }),
+            (
+                "CSQPZYRU1CE8C",
+                DecodeError::InvalidChar {
+                    index: 7,
+                    byte: b'U',
+                },
+            ),
+            (
+                "CSQP!YRK1CE8C",
+                DecodeError::InvalidChar {
+                    index: 4,
+                    byte: b'!',
+                },
+            ),
+            ("0K90Q0", DecodeError::InvalidLength { length: 6 }),
+            ("CSQPZYRK1CE8é", DecodeError::NonAscii { index: 12 }),
+        ] {
+            assert_eq!(decode_crockford_interleaved_check(data, 4), Err(error));
+        }
+    }
+
+    #[test]
+    fn invertible_interleaved_check() {
+        fn test(data: Vec<u8>, group: usize) -> bool {
+            let group = group % 8 + 1;
+            let encoded = encode_crockford_interleaved_check(&data, group);
+            decode_crockford_interleaved_check(&encoded, group).unwrap() == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize) -> bool)
+    }
+
+    #[test]
+    fn invertible_trim() {
+        fn test(data: Vec<u8>) -> bool {
+            decode_crockford_trim(&encode_crockford_trim(&data), data.len()).unwrap() == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool)
+    }
+}
@@ -0,0 +1,211 @@
+/// Builds a [`CustomAlphabet`] from a string of 32 distinct symbols at compile time.
+///
+/// The forward table is the string itself and the inverse table maps every other byte to `-1`.
+/// The symbols are checked in a `const` context, so a wrong length, a repeated, non-ASCII or
+/// control symbol, or the padding character `=` fails the build instead of producing a broken
+/// table. Control characters such as NUL are therefore never symbols, and always decode to
+/// [`DecodeError::InvalidChar`](crate::DecodeError::InvalidChar).
+///
+/// ```
+/// const HEX: base32::CustomAlphabet = base32::base32_alphabet!("0123456789ABCDEFGHIJKLMNOPQRSTUV");
+///
+/// assert_eq!(HEX.symbols()[31], b'V');
+/// assert_eq!(HEX.values()[b'V' as usize], 31);
+/// assert_eq!(HEX.values()[b'W' as usize], -1);
+/// ```
+///
+/// ```compile_fail
+/// // `A` appears twice.
+/// const BAD: base32::CustomAlphabet = base32::base32_alphabet!("AACDEFGHIJKLMNOPQRSTUVWXYZ234567");
+/// ```
+#[macro_export]
+macro_rules! base32_alphabet {
+    ($symbols:expr) => {{
+        const ALPHABET: $crate::CustomAlphabet = $crate::CustomAlphabet::new($symbols);
+        ALPHABET
+    }};
+}
+
+use super::Base32Error;
+
+/// Forward and inverse lookup tables for a 32-symbol alphabet.
+#[derive(Copy, Clone, Debug)]
+pub struct CustomAlphabet {
+    symbols: [u8; 32],
+    values: [i8; 256],
+}
+
+impl CustomAlphabet {
+    /// Builds the tables for `symbols`, which must be 32 distinct ASCII characters other than
+    /// control characters and `=`.
+    ///
+    /// This is a `const fn`, so a `const` or `static` alphabet has both tables built at compile
+    /// time and costs nothing at runtime; [`base32_alphabet!`] is shorthand for that. Invalid
+    /// symbols then fail the build. Called at runtime, it panics instead.
+    ///
+    /// ```
+    /// use base32::{Alphabet, CustomAlphabet};
+    ///
+    /// static HEX: CustomAlphabet = CustomAlphabet::new("0123456789ABCDEFGHIJKLMNOPQRSTUV");
+    ///
+    /// assert_eq!(HEX.values()[b'V' as usize], 31);
+    /// assert_eq!(base32::encode(Alphabet::Custom(&HEX), b"foo"), "CPNMU");
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use base32::CustomAlphabet;
+    ///
+    /// // Only 31 symbols.
+    /// const BAD: CustomAlphabet = CustomAlphabet::new("0123456789ABCDEFGHIJKLMNOPQRSTU");
+    /// ```
+    pub const fn new(symbols: &str) -> CustomAlphabet {
+        let symbols = symbols.as_bytes();
+        assert!(
+            symbols.len() == 32,
+            "a base32 alphabet needs exactly 32 symbols"
+        );
+
+        let mut ret = CustomAlphabet {
+            symbols: [0; 32],
+            values: [-1; 256],
+        };
+        let mut i = 0;
+        while i < 32 {
+            let c = symbols[i];
+            assert!(c.is_ascii(), "base32 symbols must be ASCII");
+            assert!(!c.is_ascii_control(), "base32 symbols must not be control characters");
+            assert!(c != b'=', "`=` is reserved for padding");
+            assert!(
+                ret.values[c as usize] == -1,
+                "base32 symbols must be unique"
+            );
+            ret.symbols[i] = c;
+            ret.values[c as usize] = i as i8;
+            i += 1;
+        }
+        ret
+    }
+
+    /// Like [`new`](CustomAlphabet::new), but for symbols only known at runtime, such as
+    /// from a configuration file: invalid symbols give [`Base32Error::InvalidAlphabet`]
+    /// instead of a panic. [`Alphabet::Custom`](crate::Alphabet::Custom) takes a `'static`
+    /// reference, so keep the result in a `static` such as a `OnceLock`, or leak it.
+    ///
+    /// ```
+    /// use base32::{Base32Error, CustomAlphabet};
+    ///
+    /// assert!(CustomAlphabet::try_new("0123456789ABCDEFGHIJKLMNOPQRSTUV").is_ok());
+    /// assert_eq!(
+    ///     CustomAlphabet::try_new("0123456789ABCDEFGHIJKLMNOPQRSTUU").unwrap_err(),
+    ///     Base32Error::InvalidAlphabet
+    /// );
+    /// ```
+    pub fn try_new(symbols: &str) -> Result<CustomAlphabet, Base32Error> {
+        let bytes = symbols.as_bytes();
+        let valid = bytes.len() == 32
+            && bytes.iter().enumerate().all(|(i, &c)| {
+                c.is_ascii() && !c.is_ascii_control() && c != b'=' && !bytes[..i].contains(&c)
+            });
+        if !valid {
+            return Err(Base32Error::InvalidAlphabet);
+        }
+        Ok(CustomAlphabet::new(symbols))
+    }
+
+    /// Makes `alias` decode to the same value as `symbol`.
+    pub(crate) const fn alias(mut self, alias: u8, symbol: u8) -> CustomAlphabet {
+        self.values[alias as usize] = self.values[symbol as usize];
+        self
+    }
+
+    /// Makes every letter decode the same as its other-case counterpart if that is a symbol.
+    pub(crate) const fn fold_case(mut self) -> CustomAlphabet {
+        let mut c = 0;
+        while c < 128 {
+            let other = (c as u8 ^ 0x20) as usize;
+            if (c as u8).is_ascii_alphabetic() && self.values[c] == -1 {
+                self.values[c] = self.values[other];
+            }
+            c += 1;
+        }
+        self
+    }
+
+    /// The symbol for each 5-bit value.
+    pub const fn symbols(&self) -> &[u8; 32] {
+        &self.symbols
+    }
+
+    /// The 5-bit value for each byte, or `-1` if the byte is not a symbol.
+    pub const fn values(&self) -> &[i8; 256] {
+        &self.values
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::Base32Error;
+    use super::CustomAlphabet;
+
+    #[test]
+    fn inverse_matches_symbols() {
+        const ALPHABET: CustomAlphabet = base32_alphabet!("ybndrfg8ejkmcpqxot1uwisza345h769");
+        for (i, &c) in ALPHABET.symbols().iter().enumerate() {
+            assert_eq!(ALPHABET.values()[c as usize], i as i8);
+        }
+        let valid = ALPHABET.values().iter().filter(|&&v| v != -1).count();
+        assert_eq!(valid, 32);
+    }
+
+    #[test]
+    fn const_matches_runtime() {
+        const SYMBOLS: &str = "ybndrfg8ejkmcpqxot1uwisza345h769";
+        const ALPHABET: CustomAlphabet = CustomAlphabet::new(SYMBOLS);
+        let runtime = CustomAlphabet::new(SYMBOLS);
+        assert_eq!(ALPHABET.symbols(), runtime.symbols());
+        assert_eq!(ALPHABET.values()[..], runtime.values()[..]);
+    }
+
+    #[test]
+    fn try_new() {
+        const SYMBOLS: &str = "ybndrfg8ejkmcpqxot1uwisza345h769";
+        let alphabet = CustomAlphabet::try_new(SYMBOLS).unwrap();
+        assert_eq!(alphabet.symbols(), CustomAlphabet::new(SYMBOLS).symbols());
+        for symbols in [
+            "0123456789",
+            "AACDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            "\0BCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            "=BCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            "éCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+        ] {
+            assert_eq!(
+                CustomAlphabet::try_new(symbols).unwrap_err(),
+                Base32Error::InvalidAlphabet
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exactly 32 symbols")]
+    fn wrong_length() {
+        CustomAlphabet::new("0123456789");
+    }
+
+    #[test]
+    #[should_panic(expected = "unique")]
+    fn duplicate_symbol() {
+        CustomAlphabet::new("AACDEFGHIJKLMNOPQRSTUVWXYZ234567");
+    }
+
+    #[test]
+    #[should_panic(expected = "control characters")]
+    fn control_symbol() {
+        CustomAlphabet::new("\0BCDEFGHIJKLMNOPQRSTUVWXYZ234567");
+    }
+
+    #[test]
+    #[should_panic(expected = "reserved for padding")]
+    fn padding_symbol() {
+        CustomAlphabet::new("=BCDEFGHIJKLMNOPQRSTUVWXYZ234567");
+    }
+}
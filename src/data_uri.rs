@@ -0,0 +1,90 @@
+//! `data:` URIs with a base32 payload.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use super::{Alphabet, Config, DecodeError};
+
+/// Parses a `data:` URI whose payload is base32, such as
+/// `data:application/octet-stream;base32,MZXW6===`, returning its media type and the decoded
+/// payload.
+///
+/// The media type is everything between `data:` and `;base32`, parameters included, or `None`
+/// if it is empty. The payload is RFC4648 base32; as it is part of a URI, its padding may be
+/// percent-encoded as `%3D`. Error indices refer to positions in `s`.
+///
+/// Returns [`DecodeError::InvalidDataUri`] if `s` does not start with `data:`, has no `,`, or
+/// names an encoding other than base32, e.g. `;base64`.
+///
+/// ```
+/// use base32::{decode_data_uri, DecodeError};
+///
+/// let (mime, data) = decode_data_uri("data:text/plain;charset=utf-8;base32,MZXW6===").unwrap();
+/// assert_eq!(mime.as_deref(), Some("text/plain;charset=utf-8"));
+/// assert_eq!(data, b"foo");
+///
+/// assert_eq!(decode_data_uri("data:;base32,MZXW6%3D%3D%3D").unwrap(), (None, b"foo".to_vec()));
+/// assert_eq!(decode_data_uri("data:;base64,Zm9v"), Err(DecodeError::InvalidDataUri));
+/// ```
+pub fn decode_data_uri(s: &str) -> Result<(Option<String>, Vec<u8>), DecodeError> {
+    let scheme = s.get(..5).ok_or(DecodeError::InvalidDataUri)?;
+    if !scheme.eq_ignore_ascii_case("data:") {
+        return Err(DecodeError::InvalidDataUri);
+    }
+    let comma = s.find(',').ok_or(DecodeError::InvalidDataUri)?;
+    let header = &s[5..comma];
+    let mime = match header.rfind(';') {
+        Some(i) if header[i + 1..].eq_ignore_ascii_case("base32") => &header[..i],
+        _ => return Err(DecodeError::InvalidDataUri),
+    };
+
+    let offset = comma + 1;
+    let data = Config::new(Alphabet::Rfc4648 { padding: true })
+        .percent_encoded_padding(true)
+        .decode(&s[offset..])
+        .map_err(|e| e.map_index(|index| index + offset))?;
+    let mime = if mime.is_empty() {
+        None
+    } else {
+        Some(mime.to_string())
+    };
+    Ok((mime, data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::DecodeError;
+    use super::decode_data_uri;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn data_uri() {
+        assert_eq!(
+            decode_data_uri("DATA:application/octet-stream;BASE32,").unwrap(),
+            (Some("application/octet-stream".into()), Vec::new())
+        );
+        assert_eq!(
+            decode_data_uri("data:;base32,MZXW!==="),
+            Err(DecodeError::InvalidChar {
+                index: 17,
+                byte: b'!'
+            })
+        );
+        for &uri in &[
+            "",
+            "data",
+            "base32,MZXW6===",
+            "data:;base32",
+            "data:base32,MZXW6===",
+            "data:text/plain,foo",
+            "data:;base32hex,CPNMU===",
+        ] {
+            assert_eq!(
+                decode_data_uri(uri),
+                Err(DecodeError::InvalidDataUri),
+                "{}",
+                uri
+            );
+        }
+    }
+}
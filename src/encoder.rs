@@ -0,0 +1,147 @@
+//! Lazy encoding into an iterator of characters.
+
+use core::iter::FusedIterator;
+
+use super::{encode_chunk, Alphabet, Config, INPUT_CHUNK, OUTPUT_CHUNK};
+
+/// Borrowed data to be encoded with a [`Config`], for use in iterator pipelines.
+///
+/// Iterating over an `Encoder` yields the characters of [`Config::encode`] one at a time,
+/// encoding a group of 5 bytes whenever the previous one runs out, so no `String` is
+/// allocated.
+///
+/// ```
+/// use base32::{Alphabet, Encoder};
+///
+/// let mut encoded = String::new();
+/// for c in Encoder::new(Alphabet::Crockford, b"foobar") {
+///     encoded.push(c);
+/// }
+/// assert_eq!(encoded, "CSQPYRK1E8");
+/// ```
+#[derive(Copy, Clone)]
+pub struct Encoder<'a> {
+    config: Config,
+    data: &'a [u8],
+}
+
+impl<'a> Encoder<'a> {
+    pub fn new(alphabet: Alphabet, data: &'a [u8]) -> Encoder<'a> {
+        Encoder::with_config(Config::new(alphabet), data)
+    }
+
+    pub fn with_config(config: Config, data: &'a [u8]) -> Encoder<'a> {
+        Encoder { config, data }
+    }
+}
+
+impl<'a> IntoIterator for Encoder<'a> {
+    type Item = char;
+    type IntoIter = EncodeChars<'a>;
+
+    fn into_iter(self) -> EncodeChars<'a> {
+        EncodeChars {
+            config: self.config,
+            data: self.data,
+            buf: [0; OUTPUT_CHUNK],
+            pos: 0,
+            len: 0,
+            newline: self.config.trailing_newline,
+        }
+    }
+}
+
+/// The characters of an encoding, created by iterating over an [`Encoder`].
+#[derive(Clone)]
+pub struct EncodeChars<'a> {
+    config: Config,
+    /// The bytes not yet encoded into `buf`.
+    data: &'a [u8],
+    buf: [u8; OUTPUT_CHUNK],
+    pos: usize,
+    len: usize,
+    /// Whether a trailing newline is still to be yielded.
+    newline: bool,
+}
+
+impl<'a> Iterator for EncodeChars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.pos == self.len {
+            if self.data.is_empty() {
+                return if self.newline {
+                    self.newline = false;
+                    Some('\n')
+                } else {
+                    None
+                };
+            }
+            let (chunk, rest) = self.data.split_at(self.data.len().min(INPUT_CHUNK));
+            self.data = rest;
+            let symbols = self.config.alphabet.table().symbols();
+            self.buf = encode_chunk(symbols, chunk, self.config.bit_order);
+            self.len = (chunk.len() * 8).div_ceil(5);
+            if self.config.padding {
+                for b in &mut self.buf[self.len..] {
+                    *b = b'=';
+                }
+                self.len = OUTPUT_CHUNK;
+            }
+            self.pos = 0;
+        }
+        let c = self.buf[self.pos];
+        self.pos += 1;
+        Some(c as char)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = self
+            .config
+            .trailing_newline(false)
+            .encoded_len(self.data.len());
+        let len = self.len - self.pos + rest + usize::from(self.newline);
+        (len, Some(len))
+    }
+}
+
+impl<'a> ExactSizeIterator for EncodeChars<'a> {}
+
+impl<'a> FusedIterator for EncodeChars<'a> {}
+
+#[cfg(test)]
+mod test {
+    use super::super::{encode, Alphabet, BitOrder, Config};
+    use super::Encoder;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn collect_matches_encode() {
+        fn test(data: Vec<u8>, padding: bool) -> bool {
+            let alphabet = Alphabet::Rfc4648 { padding };
+            let encoder = Encoder::new(alphabet, &data);
+            encoder.into_iter().len() == encode(alphabet, &data).len()
+                && encoder.into_iter().collect::<String>() == encode(alphabet, &data)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, bool) -> bool);
+    }
+
+    #[test]
+    fn config() {
+        let config = Config::new(Alphabet::Crockford)
+            .padding(true)
+            .bit_order(BitOrder::LsbFirst)
+            .trailing_newline(true);
+        for data in [&b""[..], b"f", b"foobar", b"fooba"] {
+            let mut chars = Encoder::with_config(config, data).into_iter();
+            let mut encoded = String::new();
+            while let Some(c) = chars.next() {
+                encoded.push(c);
+                assert_eq!(chars.len(), config.encode(data).len() - encoded.len());
+            }
+            assert_eq!(encoded, config.encode(data));
+            assert_eq!(chars.next(), None);
+        }
+    }
+}
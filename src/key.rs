@@ -0,0 +1,140 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use super::sink::FmtSink;
+use super::{Alphabet, Config, DecodeError};
+
+/// An identifier parsed from base32 that compares, orders and hashes by its decoded bytes and
+/// displays as the canonical encoding of them, so it can key a `HashMap` or `BTreeMap`
+/// whatever case or padding the input used.
+///
+/// The alphabet only affects how the key is displayed: two keys holding the same bytes are
+/// equal even if they were parsed with different alphabets.
+///
+/// ```
+/// use base32::{Alphabet, Base32Key};
+///
+/// let a = Base32Key::parse(Alphabet::Crockford, "csqpyrk1e8").unwrap();
+/// let b = Base32Key::parse(Alphabet::Crockford, "CSQPYRKIE8").unwrap();
+/// assert_eq!(a, b);
+/// assert_eq!(a.as_bytes(), b"foobar");
+/// assert_eq!(a.to_string(), "CSQPYRK1E8");
+/// ```
+#[derive(Clone)]
+pub struct Base32Key {
+    alphabet: Alphabet,
+    bytes: Vec<u8>,
+}
+
+impl Base32Key {
+    /// Decodes `s` with `alphabet`.
+    pub fn parse(alphabet: Alphabet, s: &str) -> Result<Base32Key, DecodeError> {
+        Ok(Base32Key {
+            alphabet,
+            bytes: Config::new(alphabet).decode(s)?,
+        })
+    }
+
+    /// The alphabet the key is displayed in.
+    pub fn alphabet(&self) -> Alphabet {
+        self.alphabet
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl AsRef<[u8]> for Base32Key {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl PartialEq for Base32Key {
+    fn eq(&self, other: &Base32Key) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl Eq for Base32Key {}
+
+impl PartialOrd for Base32Key {
+    fn partial_cmp(&self, other: &Base32Key) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Base32Key {
+    fn cmp(&self, other: &Base32Key) -> Ordering {
+        self.bytes.cmp(&other.bytes)
+    }
+}
+
+impl Hash for Base32Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state);
+    }
+}
+
+impl fmt::Display for Base32Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sink = FmtSink::new(f);
+        Config::new(self.alphabet).encode_into(&self.bytes, &mut sink);
+        sink.finish()
+    }
+}
+
+impl fmt::Debug for Base32Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Base32Key")
+            .field(&format_args!("{}", self))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::Alphabet;
+    use super::Base32Key;
+    use alloc::collections::BTreeMap;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn ordered_by_bytes() {
+        let alphabet = Alphabet::Rfc4648 { padding: true };
+        let keys = ["MZXW6===", "AE======", "MZXW6YQ=", "", "MY"];
+        let mut map = BTreeMap::new();
+        for (i, &key) in keys.iter().enumerate() {
+            map.insert(Base32Key::parse(alphabet, key).unwrap(), i);
+        }
+        let sorted = map.keys().map(|key| key.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(sorted, [&b""[..], b"\x01", b"f", b"foo", b"foob"]);
+
+        // Display round-trips and fills in the padding the input left out.
+        let key = Base32Key::parse(alphabet, "MY").unwrap();
+        assert_eq!(key.to_string(), "MY======");
+        assert_eq!(Base32Key::parse(alphabet, &key.to_string()).unwrap(), key);
+        assert_eq!(map[&key], 4);
+        assert!(Base32Key::parse(alphabet, "M!").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hashes_by_bytes() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert(Base32Key::parse(Alphabet::Crockford, "CSQPYRK1E8").unwrap());
+        assert!(set.contains(&Base32Key::parse(Alphabet::Crockford, "csqpyrkie8").unwrap()));
+        let rfc4648 = Alphabet::Rfc4648 { padding: false };
+        assert!(set.contains(&Base32Key::parse(rfc4648, "MZXW6YTBOI").unwrap()));
+    }
+}
@@ -1,463 +1,5051 @@
 #![no_std]
+// The only `unsafe` is the file mapping behind the opt-in `memmap2` feature, which can only
+// lower `forbid` to `deny` and allow it on that one call.
+#![cfg_attr(not(feature = "memmap2"), forbid(unsafe_code))]
+#![cfg_attr(feature = "memmap2", deny(unsafe_code))]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 #[cfg(test)]
 extern crate quickcheck;
+#[cfg(feature = "bytes")]
+extern crate bytes;
+#[cfg(feature = "memmap2")]
+extern crate memmap2;
+#[cfg(feature = "rand")]
+extern crate rand;
 
+use alloc::borrow::Cow;
 use alloc::string::String;
 use alloc::vec::Vec;
-use core::cmp::min;
+use core::fmt;
+use core::ops::Range;
+
+#[macro_use]
+mod custom;
+
+pub use custom::CustomAlphabet;
+pub use small::SmallBytes;
+pub use state::DecodeState;
+
+mod base16;
+#[cfg(feature = "bytes")]
+mod buf;
+mod crockford;
+mod data_uri;
+mod encoder;
+mod key;
+#[cfg(feature = "memmap2")]
+mod mmap;
+#[cfg(feature = "nix")]
+mod nix;
+#[cfg(feature = "std")]
+mod os_str;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod radix;
+#[cfg(feature = "rand")]
+mod random;
+mod rotate;
+#[cfg(feature = "std")]
+mod scratch;
+mod sink;
+mod small;
+mod state;
+#[cfg(feature = "std")]
+mod stream;
+
+pub use base16::{base32_to_hex, hex_to_base32};
+#[cfg(feature = "bytes")]
+pub use buf::{decode_buf, encode_buf};
+pub use crockford::{
+    crockford_check_symbol, decode_crockford_interleaved_check, decode_crockford_trim,
+    decode_license_key, decode_u128_crockford, decode_u64_crockford_fixed,
+    encode_crockford_interleaved_check, encode_crockford_trim, encode_license_key,
+    encode_license_key_cased, encode_u128_crockford, encode_u64_crockford_fixed,
+    regroup_crockford,
+};
+
+pub use data_uri::decode_data_uri;
+pub use encoder::{EncodeChars, Encoder};
+pub use key::Base32Key;
+#[cfg(feature = "memmap2")]
+pub use mmap::encode_mmap;
+#[cfg(feature = "nix")]
+pub use nix::{decode_nix, encode_nix};
+#[cfg(feature = "std")]
+pub use os_str::decode_os_str;
+#[cfg(feature = "parallel")]
+pub use parallel::{encode_par, validate_batch_par};
+pub use radix::Radix;
+#[cfg(feature = "rand")]
+pub use random::random;
+pub use rotate::{decode_rotated, encode_rotated};
+use sink::ByteSink;
+#[cfg(feature = "std")]
+pub use scratch::decode_scratch;
+#[cfg(feature = "std")]
+pub use stream::{
+    decode_to_writer, encode_from_reader, transcode, DecodeReader, DecodeWriter, EncodeReader,
+    EncodeWriter,
+};
+
+/// Re-exports of the commonly used items: [`encode`], [`decode`], [`Alphabet`], [`Config`] and
+/// [`DecodeError`]. Everything here is also available from the crate root.
+///
+/// ```
+/// use base32::prelude::*;
+///
+/// let config = Config::new(Alphabet::Crockford);
+/// assert_eq!(encode(Alphabet::Crockford, b"hi"), config.encode(b"hi"));
+/// assert_eq!(decode(Alphabet::Crockford, "D1MG").unwrap(), b"hi");
+/// assert!(matches!(config.decode("U"), Err(DecodeError::InvalidChar { .. })));
+/// ```
+pub mod prelude {
+    pub use super::{decode, encode, Alphabet, Config, DecodeError};
+}
+
+/// Number of input bytes in one group. Each group encodes to [`OUTPUT_CHUNK`] symbols.
+pub const INPUT_CHUNK: usize = 5;
+/// Number of symbols one group of [`INPUT_CHUNK`] bytes encodes to, and the multiple padded
+/// output is rounded up to.
+pub const OUTPUT_CHUNK: usize = 8;
 
 #[derive(Copy, Clone)]
 pub enum Alphabet {
     Crockford,
     Rfc4648 { padding: bool },
     Rfc4648Lower { padding: bool },
+    /// RFC4648 base32hex. Requires the `hex` feature.
+    #[cfg(feature = "hex")]
     Rfc4648Hex { padding: bool },
+    /// Lowercase RFC4648 base32hex. Requires the `hex` feature.
+    #[cfg(feature = "hex")]
     Rfc4648HexLower { padding: bool },
+    /// z-base-32. Requires the `zbase32` feature.
+    #[cfg(feature = "zbase32")]
     Z,
+    /// The data part of Bech32 (BIP 173), without the human-readable part, separator and
+    /// checksum, which this crate does not handle. Requires the `bech32` feature.
+    ///
+    /// Bech32 packs bits the same way as unpadded RFC4648: 8-bit bytes are regrouped into
+    /// 5-bit values most significant bit first, and the last value is filled with zero bits.
+    /// Only the symbols differ. As in Bech32, lengths that leave 5 or more bits over are
+    /// rejected.
+    #[cfg(feature = "bech32")]
+    Bech32,
+    /// A user-supplied alphabet, usually built with [`base32_alphabet!`]. It is unpadded; use
+    /// [`Config::padding`] to pad it.
+    ///
+    /// The table is borrowed for `'static` so that `Alphabet` stays `Copy` and cheap to pass
+    /// by value. A `const` or `static` table works directly; one built at runtime can be
+    /// leaked with `Box::leak` once and reused.
+    ///
+    /// ```
+    /// use base32::{Alphabet, CustomAlphabet};
+    ///
+    /// static HEX: CustomAlphabet = base32::base32_alphabet!("0123456789ABCDEFGHIJKLMNOPQRSTUV");
+    ///
+    /// assert_eq!(base32::encode(Alphabet::Custom(&HEX), b"foo"), "CPNMU");
+    /// assert_eq!(base32::decode(Alphabet::Custom(&HEX), "CPNMU").unwrap(), b"foo");
+    /// ```
+    Custom(&'static CustomAlphabet),
+}
+
+const CROCKFORD: CustomAlphabet = base32_alphabet!("0123456789ABCDEFGHJKMNPQRSTVWXYZ")
+    .alias(b'I', b'1')
+    .alias(b'L', b'1')
+    .alias(b'O', b'0')
+    .fold_case();
+const RFC4648: CustomAlphabet = base32_alphabet!("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567");
+const RFC4648_LOWER: CustomAlphabet = base32_alphabet!("abcdefghijklmnopqrstuvwxyz234567");
+#[cfg(feature = "hex")]
+const RFC4648_HEX: CustomAlphabet = base32_alphabet!("0123456789ABCDEFGHIJKLMNOPQRSTUV");
+#[cfg(feature = "hex")]
+const RFC4648_HEX_LOWER: CustomAlphabet = base32_alphabet!("0123456789abcdefghijklmnopqrstuv");
+#[cfg(feature = "zbase32")]
+const Z: CustomAlphabet = base32_alphabet!("ybndrfg8ejkmcpqxot1uwisza345h769");
+#[cfg(feature = "bech32")]
+const BECH32: CustomAlphabet = base32_alphabet!("qpzry9x8gf2tvdw0s3jn54khce6mua7l");
+
+/// Letter case of encoded symbols.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Case {
+    Upper,
+    Lower,
+}
+
+impl Alphabet {
+    /// The case [`encode`] emits letters in, which is the one the alphabet's specification
+    /// uses: upper for Crockford and the RFC4648 variants, lower for their `Lower` variants
+    /// and z-base-32. A custom alphabet is [`Case::Lower`] if it has lowercase letters and no
+    /// uppercase ones.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Case};
+    ///
+    /// assert_eq!(Alphabet::Crockford.canonical_case(), Case::Upper);
+    /// assert_eq!(Alphabet::Rfc4648Lower { padding: false }.canonical_case(), Case::Lower);
+    /// ```
+    pub fn canonical_case(self) -> Case {
+        match self {
+            Alphabet::Crockford | Alphabet::Rfc4648 { .. } => Case::Upper,
+            Alphabet::Rfc4648Lower { .. } => Case::Lower,
+            #[cfg(feature = "hex")]
+            Alphabet::Rfc4648Hex { .. } => Case::Upper,
+            #[cfg(feature = "hex")]
+            Alphabet::Rfc4648HexLower { .. } => Case::Lower,
+            #[cfg(feature = "zbase32")]
+            Alphabet::Z => Case::Lower,
+            #[cfg(feature = "bech32")]
+            Alphabet::Bech32 => Case::Lower,
+            Alphabet::Custom(table) => {
+                let symbols = table.symbols();
+                if symbols.iter().any(u8::is_ascii_lowercase)
+                    && !symbols.iter().any(u8::is_ascii_uppercase)
+                {
+                    Case::Lower
+                } else {
+                    Case::Upper
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn table(self) -> &'static CustomAlphabet {
+        match self {
+            Alphabet::Crockford => &CROCKFORD, // supports both upper and lower case
+            Alphabet::Rfc4648 { .. } => &RFC4648,
+            Alphabet::Rfc4648Lower { .. } => &RFC4648_LOWER,
+            #[cfg(feature = "hex")]
+            Alphabet::Rfc4648Hex { .. } => &RFC4648_HEX,
+            #[cfg(feature = "hex")]
+            Alphabet::Rfc4648HexLower { .. } => &RFC4648_HEX_LOWER,
+            #[cfg(feature = "zbase32")]
+            Alphabet::Z => &Z,
+            #[cfg(feature = "bech32")]
+            Alphabet::Bech32 => &BECH32,
+            Alphabet::Custom(table) => table,
+        }
+    }
+
+    /// The number of bits each symbol carries: 5 for every base32 alphabet.
+    pub fn bits_per_char(self) -> u32 {
+        5
+    }
+
+    /// The number of symbols in the alphabet: 32 for every base32 alphabet. Aliases such as
+    /// Crockford's `I`, `L` and `O` and the other letter case are not counted.
+    pub fn alphabet_size(self) -> usize {
+        32
+    }
+
+    /// Whether [`encode`] pads its output to a multiple of 8 characters. Only the RFC4648
+    /// variants can be padded; use [`Config::padding`] to pad any alphabet.
+    ///
+    /// ```
+    /// use base32::Alphabet;
+    ///
+    /// assert!(Alphabet::Rfc4648 { padding: true }.is_padded());
+    /// assert!(!Alphabet::Crockford.is_padded());
+    /// ```
+    #[inline]
+    pub fn is_padded(self) -> bool {
+        match self {
+            Alphabet::Crockford | Alphabet::Custom(_) => false,
+            #[cfg(feature = "zbase32")]
+            Alphabet::Z => false,
+            #[cfg(feature = "bech32")]
+            Alphabet::Bech32 => false,
+            Alphabet::Rfc4648 { padding } | Alphabet::Rfc4648Lower { padding } => padding,
+            #[cfg(feature = "hex")]
+            Alphabet::Rfc4648Hex { padding } | Alphabet::Rfc4648HexLower { padding } => padding,
+        }
+    }
+
+    /// The padding character [`encode`] appends, `=`, or `None` if the alphabet is not
+    /// [padded](Alphabet::is_padded).
+    pub fn pad_char(self) -> Option<u8> {
+        if self.is_padded() {
+            Some(b'=')
+        } else {
+            None
+        }
+    }
+
+    fn is_rfc4648(self) -> bool {
+        match self {
+            Alphabet::Rfc4648 { .. } | Alphabet::Rfc4648Lower { .. } => true,
+            #[cfg(feature = "hex")]
+            Alphabet::Rfc4648Hex { .. } | Alphabet::Rfc4648HexLower { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// Error returned by [`Config::decode`].
+#[must_use]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The byte at `index` is not part of the alphabet.
+    InvalidChar { index: usize, byte: u8 },
+    /// No input encodes to `length` symbols (excluding padding), e.g. a single symbol carries
+    /// 5 bits, which is not enough for a byte.
+    InvalidLength { length: usize },
+    /// The character starting at byte `index` is not ASCII, so it cannot be a symbol. This is
+    /// usually a look-alike such as a full-width letter or a typographic quote picked up by
+    /// copy and paste.
+    NonAscii { index: usize },
+    /// The input or the decoded output is `got` long where the caller required `expected`:
+    /// symbols for [`decode_fields`], bytes for [`decode_exact`], and characters or bytes for
+    /// [`decode_hash32`].
+    UnexpectedLength { got: usize, expected: usize },
+    /// The input to [`decode_unpadded_exact`] decodes to `got` bytes where the caller required
+    /// `expected`.
+    LengthMismatch { got: usize, expected: usize },
+    /// The byte at `index` is the digit `0`, `1`, `8` or `9`, which the RFC4648 alphabet does
+    /// not use but Crockford's and base32hex do. This usually means the wrong alphabet was
+    /// chosen.
+    InvalidDigit { index: usize, byte: u8 },
+    /// The byte at `index` is `U` or `u`, which Crockford's Base32 leaves out so that encoded
+    /// text cannot accidentally spell common obscenities. Only reported with
+    /// [`Config::excluded_symbols`].
+    ExcludedSymbol { index: usize, byte: u8 },
+    /// The input ends in `actual` padding characters where its length calls for `expected`.
+    /// Only reported with [`Config::strict_padding`].
+    InvalidPadding { expected: usize, actual: usize },
+    /// The input to [`decode_data_uri`] is not a `data:` URI with a base32 payload.
+    InvalidDataUri,
+    /// The input to [`decode_trimmed`] does not start with the required prefix.
+    MissingPrefix,
+    /// The input to [`decode_trimmed`] does not end with the required suffix.
+    MissingSuffix,
+    /// The input to [`decode_any`] does not decode with any of the built-in alphabets.
+    NoMatchingAlphabet,
+    /// The check symbol after group `group` (counting from 0) of the input to
+    /// [`decode_crockford_interleaved_check`] does not match the symbols before it.
+    CheckMismatch { group: usize },
+}
+
+impl DecodeError {
+    /// The input position the error refers to, if any.
+    fn index(self) -> Option<usize> {
+        match self {
+            DecodeError::InvalidChar { index, .. }
+            | DecodeError::InvalidDigit { index, .. }
+            | DecodeError::ExcludedSymbol { index, .. }
+            | DecodeError::NonAscii { index } => Some(index),
+            DecodeError::InvalidLength { .. }
+            | DecodeError::UnexpectedLength { .. }
+            | DecodeError::LengthMismatch { .. }
+            | DecodeError::InvalidPadding { .. }
+            | DecodeError::InvalidDataUri
+            | DecodeError::MissingPrefix
+            | DecodeError::MissingSuffix
+            | DecodeError::NoMatchingAlphabet
+            | DecodeError::CheckMismatch { .. } => None,
+        }
+    }
+
+    fn map_index<F: FnOnce(usize) -> usize>(self, f: F) -> DecodeError {
+        match self {
+            DecodeError::InvalidChar { index, byte } => DecodeError::InvalidChar {
+                index: f(index),
+                byte,
+            },
+            DecodeError::InvalidDigit { index, byte } => DecodeError::InvalidDigit {
+                index: f(index),
+                byte,
+            },
+            DecodeError::ExcludedSymbol { index, byte } => DecodeError::ExcludedSymbol {
+                index: f(index),
+                byte,
+            },
+            DecodeError::NonAscii { index } => DecodeError::NonAscii { index: f(index) },
+            DecodeError::InvalidLength { .. }
+            | DecodeError::UnexpectedLength { .. }
+            | DecodeError::LengthMismatch { .. }
+            | DecodeError::InvalidPadding { .. }
+            | DecodeError::InvalidDataUri
+            | DecodeError::MissingPrefix
+            | DecodeError::MissingSuffix
+            | DecodeError::NoMatchingAlphabet
+            | DecodeError::CheckMismatch { .. } => self,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DecodeError::InvalidChar { index, byte } => {
+                write!(f, "invalid character {:?} at index {}", byte as char, index)
+            }
+            DecodeError::InvalidLength { length } => {
+                write!(f, "invalid length: no input encodes to {} symbols", length)
+            }
+            DecodeError::NonAscii { index } => {
+                write!(f, "non-ASCII character at index {}", index)
+            }
+            DecodeError::UnexpectedLength { got, expected } => {
+                write!(f, "unexpected length {}, expected {}", got, expected)
+            }
+            DecodeError::LengthMismatch { got, expected } => {
+                write!(f, "decodes to {} bytes, expected {}", got, expected)
+            }
+            DecodeError::InvalidDigit { index, byte } => write!(
+                f,
+                "digit {:?} at index {} is not valid in the RFC4648 alphabet, which only uses 2-7; \
+                 did you mean Crockford or base32hex?",
+                byte as char, index
+            ),
+            DecodeError::ExcludedSymbol { index, byte } => write!(
+                f,
+                "{:?} at index {} is not used by Crockford's Base32, which excludes U",
+                byte as char, index
+            ),
+            DecodeError::InvalidPadding { expected, actual } => {
+                write!(f, "invalid padding: {} `=`, expected {}", actual, expected)
+            }
+            DecodeError::InvalidDataUri => write!(f, "not a base32 data: URI"),
+            DecodeError::MissingPrefix => write!(f, "missing prefix"),
+            DecodeError::MissingSuffix => write!(f, "missing suffix"),
+            DecodeError::NoMatchingAlphabet => write!(f, "no base32 alphabet decodes the input"),
+            DecodeError::CheckMismatch { group } => {
+                write!(f, "check symbol of group {} does not match", group)
+            }
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/// Error returned by any of the crate's fallible operations, for callers that use several of
+/// them and want to handle failures in one place. Decoding functions return the more specific
+/// [`DecodeError`], which converts into this with `?`.
+///
+/// ```
+/// use base32::{Alphabet, Base32Error, Config, CustomAlphabet};
+///
+/// fn roundtrip(symbols: &str, data: &[u8], out: &mut [u8]) -> Result<Vec<u8>, Base32Error> {
+///     let alphabet = Box::leak(Box::new(CustomAlphabet::try_new(symbols)?));
+///     let config = Config::new(Alphabet::Custom(alphabet));
+///     let len = config.encode_to_slice(data, out)?;
+///     Ok(config.decode(std::str::from_utf8(&out[..len]).unwrap())?)
+/// }
+///
+/// let mut out = [0; 16];
+/// assert_eq!(roundtrip("0123456789ABCDEFGHIJKLMNOPQRSTUV", b"foo", &mut out).unwrap(), b"foo");
+/// assert_eq!(roundtrip("0123", b"foo", &mut out), Err(Base32Error::InvalidAlphabet));
+/// assert_eq!(
+///     roundtrip("0123456789ABCDEFGHIJKLMNOPQRSTUV", b"foobar", &mut out[..8]),
+///     Err(Base32Error::BufferTooSmall { needed: 10, got: 8 })
+/// );
+/// ```
+#[must_use]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Base32Error {
+    /// The output buffer holds `got` bytes where the encoding needs `needed`.
+    BufferTooSmall { needed: usize, got: usize },
+    /// The symbols passed to [`CustomAlphabet::try_new`] do not make a base32 alphabet.
+    InvalidAlphabet,
+    /// The fill byte passed to [`encode_block_aligned`] or [`decode_block_aligned`], or a byte
+    /// passed to [`decode_ignoring`] to skip, is a symbol or the padding of the alphabet, or not
+    /// ASCII, so it could not be told apart from the data.
+    ConflictingByte { byte: u8 },
+    /// Decoding failed.
+    Decode(DecodeError),
+}
+
+impl From<DecodeError> for Base32Error {
+    fn from(err: DecodeError) -> Base32Error {
+        Base32Error::Decode(err)
+    }
+}
+
+impl fmt::Display for Base32Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Base32Error::BufferTooSmall { needed, got } => write!(
+                f,
+                "output buffer too small: {} bytes needed, {} available",
+                needed, got
+            ),
+            Base32Error::InvalidAlphabet => write!(
+                f,
+                "invalid alphabet: it needs 32 distinct ASCII symbols, \
+                 excluding control characters and `=`"
+            ),
+            Base32Error::ConflictingByte { byte } => write!(
+                f,
+                "byte {:?} is a symbol or the padding of the alphabet, or not ASCII",
+                byte as char
+            ),
+            Base32Error::Decode(err) => err.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for Base32Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match *self {
+            Base32Error::Decode(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The order in which the bits of the input are grouped into 5-bit symbols.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Symbols are taken from the most significant bits of each byte first, as in RFC4648.
+    MsbFirst,
+    /// Symbols are taken from the least significant bits of each byte first, and each symbol's
+    /// own bits are read least significant first. Some older systems pack bits this way.
+    LsbFirst,
+}
+
+/// The result of [`Config::decode_detailed`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoded {
+    pub data: Vec<u8>,
+    /// The number of padding characters the canonical encoding of `data` ends with. Always 0
+    /// when padding is disabled.
+    pub expected_padding: usize,
+    /// The number of padding characters present in the input.
+    pub actual_padding: usize,
+}
+
+/// An alphabet together with an independently chosen padding setting.
+///
+/// [`Alphabet`] only offers padding on the RFC4648 variants. `Config` treats the symbol set and
+/// padding as orthogonal, so padded Crockford or unpadded RFC4648 are expressed the same way.
+/// `Config::new` starts from the padding implied by the variant.
+///
+/// ```
+/// use base32::{Alphabet, Config};
+///
+/// let config = Config::new(Alphabet::Crockford).padding(true);
+/// assert_eq!(config.encode(&[0xF8]), "Z0======");
+/// assert_eq!(config.decode("Z0======").unwrap(), [0xF8]);
+///
+/// let config = Config::new(Alphabet::Rfc4648 { padding: true }).padding(false);
+/// assert_eq!(config.encode(&[0xF8]), "7A");
+/// ```
+#[derive(Copy, Clone)]
+pub struct Config {
+    alphabet: Alphabet,
+    padding: bool,
+    percent_padding: bool,
+    case_insensitive: bool,
+    ignore_whitespace: bool,
+    bit_order: BitOrder,
+    strict_padding: bool,
+    trailing_newline: bool,
+    excluded_symbols: bool,
+}
+
+impl Config {
+    pub fn new(alphabet: Alphabet) -> Config {
+        Config {
+            alphabet,
+            padding: alphabet.is_padded(),
+            percent_padding: false,
+            case_insensitive: false,
+            ignore_whitespace: false,
+            bit_order: BitOrder::MsbFirst,
+            strict_padding: false,
+            trailing_newline: false,
+            excluded_symbols: false,
+        }
+    }
+
+    /// Sets whether encoded output is padded with `=` to a multiple of 8 characters, and
+    /// whether `=` is accepted when decoding.
+    ///
+    /// When decoding, the whole run of trailing `=` is taken as padding, however long it is.
+    /// Input made only of padding, such as `"========"`, therefore has no symbols and decodes to
+    /// an empty `Vec`, unless [`strict_padding`](Config::strict_padding) is set, in which case
+    /// it fails because the empty encoding has no padding.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config, DecodeError};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: true });
+    /// assert_eq!(config.decode("========").unwrap(), b"");
+    /// assert_eq!(
+    ///     config.strict_padding(true).decode("========"),
+    ///     Err(DecodeError::InvalidPadding { expected: 0, actual: 8 })
+    /// );
+    /// ```
+    pub fn padding(self, padding: bool) -> Config {
+        Config { padding, ..self }
+    }
+
+    /// Sets whether [`decode`](Config::decode) insists on canonical padding when padding is
+    /// enabled. By default it is lenient: input whose producer stripped the trailing `=`, or
+    /// got the amount wrong, is accepted and the byte count inferred from the symbols. With
+    /// strict padding such input fails with [`DecodeError::InvalidPadding`].
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config, DecodeError};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: true });
+    /// assert_eq!(config.decode("MZXW6").unwrap(), b"foo");
+    /// assert_eq!(
+    ///     config.strict_padding(true).decode("MZXW6"),
+    ///     Err(DecodeError::InvalidPadding { expected: 3, actual: 0 })
+    /// );
+    /// ```
+    pub fn strict_padding(self, strict_padding: bool) -> Config {
+        Config {
+            strict_padding,
+            ..self
+        }
+    }
+
+    /// Sets whether a percent-encoded `=` (`%3D` or `%3d`) is accepted as padding when decoding,
+    /// as produced by URL-encoding padded base32. Only the trailing padding run is recognized; a
+    /// `%` anywhere else is still an invalid character. Has no effect unless padding is enabled.
+    pub fn percent_encoded_padding(self, percent_padding: bool) -> Config {
+        Config {
+            percent_padding,
+            ..self
+        }
+    }
+
+    /// Sets whether decoding accepts symbols in either case. Crockford is always case-insensitive.
+    pub fn case_insensitive(self, case_insensitive: bool) -> Config {
+        Config {
+            case_insensitive,
+            ..self
+        }
+    }
+
+    /// Sets whether decoding Crockford's Base32 reports `U` or `u` as
+    /// [`DecodeError::ExcludedSymbol`] rather than [`DecodeError::InvalidChar`], to explain that
+    /// Crockford leaves `U` out on purpose. Has no effect on other alphabets.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config, DecodeError};
+    ///
+    /// let config = Config::new(Alphabet::Crockford).excluded_symbols(true);
+    /// assert_eq!(
+    ///     config.decode("CSQU"),
+    ///     Err(DecodeError::ExcludedSymbol { index: 3, byte: b'U' })
+    /// );
+    /// ```
+    pub fn excluded_symbols(self, excluded_symbols: bool) -> Config {
+        Config {
+            excluded_symbols,
+            ..self
+        }
+    }
+
+    /// Sets whether ASCII whitespace is skipped anywhere in the input when decoding, so that
+    /// line-wrapped or spaced-out text decodes as if it were contiguous.
+    ///
+    /// Input that is only whitespace decodes like the empty string, to an empty `Vec`. This
+    /// holds for every decoder that skips characters: whatever is left after skipping is
+    /// decoded as usual, and nothing left is the valid encoding of no bytes.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    ///
+    /// let config = Config::new(Alphabet::Crockford).ignore_whitespace(true);
+    /// assert_eq!(config.decode(" \r\n").unwrap(), b"");
+    /// ```
+    pub fn ignore_whitespace(self, ignore_whitespace: bool) -> Config {
+        Config {
+            ignore_whitespace,
+            ..self
+        }
+    }
+
+    /// Sets the order in which bits are packed into symbols. Defaults to
+    /// [`BitOrder::MsbFirst`], as specified by RFC4648.
+    pub fn bit_order(self, bit_order: BitOrder) -> Config {
+        Config { bit_order, ..self }
+    }
+
+    /// Sets whether [`encode`](Config::encode) ends its output with a single `\n`, as the
+    /// coreutils `base32` tool does, so the result can be written straight to a file. Decoding
+    /// then accepts, but does not require, one trailing `\n`. The newline is added once per
+    /// encoding, so it is not emitted by [`encode_into`](Config::encode_into), which may be
+    /// called piecewise.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: true }).trailing_newline(true);
+    /// assert_eq!(config.encode(b"foo"), "MZXW6===\n");
+    /// assert_eq!(config.decode("MZXW6===\n").unwrap(), b"foo");
+    /// assert_eq!(config.decode("MZXW6===").unwrap(), b"foo");
+    /// ```
+    pub fn trailing_newline(self, trailing_newline: bool) -> Config {
+        Config {
+            trailing_newline,
+            ..self
+        }
+    }
+
+    /// Returns the number of characters [`encode`](Config::encode) produces for `len` bytes of
+    /// input, without encoding anything.
+    ///
+    /// The count saturates at `usize::MAX` for lengths whose encoding would not fit in a
+    /// `usize`, so it never wraps around to a small number. Such an encoding could not be
+    /// allocated anyway: a `String` holds at most `isize::MAX` bytes, which limits input to
+    /// about `isize::MAX / 8 * 5` bytes, or 1.25 GiB on 32-bit targets.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: true });
+    /// assert_eq!(config.encoded_len(3), 8);
+    /// assert_eq!(config.padding(false).encoded_len(3), 5);
+    /// ```
+    pub fn encoded_len(&self, len: usize) -> usize {
+        let symbols = if self.padding {
+            len.div_ceil(INPUT_CHUNK).saturating_mul(OUTPUT_CHUNK)
+        } else {
+            unpadded_len(len)
+        };
+        symbols.saturating_add(usize::from(self.trailing_newline))
+    }
+
+    /// Returns the largest number of bytes whose encoding fits in `char_len` characters, the
+    /// inverse of [`encoded_len`](Config::encoded_len), e.g. to size a decode buffer for input
+    /// of a known length or to choose a payload size for a fixed-width field.
+    ///
+    /// Padded output comes in whole groups of 8 characters, so only `char_len / 8 * 5` bytes
+    /// fit, while unpadded output can use every character: `char_len * 5 / 8` bytes. A
+    /// [trailing newline](Config::trailing_newline) takes one character; when there is no room
+    /// for it, not even empty input fits and 0 is returned.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: true });
+    /// assert_eq!(config.max_input_bytes(20), 10);
+    /// assert_eq!(config.padding(false).max_input_bytes(20), 12);
+    /// ```
+    pub fn max_input_bytes(&self, char_len: usize) -> usize {
+        let symbols = char_len.saturating_sub(usize::from(self.trailing_newline));
+        if self.padding {
+            symbols / OUTPUT_CHUNK * INPUT_CHUNK
+        } else {
+            decoded_len(symbols)
+        }
+    }
+
+    /// Encodes `data` into the start of `out` without allocating, and returns the number of
+    /// bytes written. Fails with [`Base32Error::BufferTooSmall`], leaving `out` untouched, if
+    /// it is shorter than [`encoded_len`](Config::encoded_len).
+    ///
+    /// ```
+    /// use base32::{Alphabet, Base32Error, Config};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: true });
+    /// let mut out = [0; 10];
+    /// assert_eq!(config.encode_to_slice(b"foo", &mut out), Ok(8));
+    /// assert_eq!(&out[..8], b"MZXW6===");
+    /// assert_eq!(
+    ///     config.encode_to_slice(b"foobar", &mut out),
+    ///     Err(Base32Error::BufferTooSmall { needed: 16, got: 10 })
+    /// );
+    /// ```
+    pub fn encode_to_slice(&self, data: &[u8], out: &mut [u8]) -> Result<usize, Base32Error> {
+        let needed = self.encoded_len(data.len());
+        if out.len() < needed {
+            return Err(Base32Error::BufferTooSmall {
+                needed,
+                got: out.len(),
+            });
+        }
+        let mut sink = &mut out[..needed];
+        self.encode_into(data, &mut sink);
+        if self.trailing_newline {
+            sink.push(b'\n');
+        }
+        Ok(needed)
+    }
+
+    /// Encodes `data`. The result is allocated with exactly
+    /// [`encoded_len`](Config::encoded_len) bytes of capacity, so it never reallocates and
+    /// wastes no space.
+    pub fn encode(&self, data: &[u8]) -> String {
+        self.encode_with_capacity(data, 0)
+    }
+
+    /// Like [`encode`](Config::encode), but allocates room for at least `capacity` bytes, for
+    /// callers that go on to append to the result, e.g. a suffix or more encoded data.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    ///
+    /// let config = Config::new(Alphabet::Crockford);
+    /// let mut url = config.encode_with_capacity(b"foobar", 15);
+    /// assert!(url.capacity() >= 15);
+    /// url.push_str(".html");
+    /// assert_eq!(url, "CSQPYRK1E8.html");
+    /// ```
+    pub fn encode_with_capacity(&self, data: &[u8], capacity: usize) -> String {
+        let mut ret = Vec::with_capacity(self.encoded_len(data.len()).max(capacity));
+        self.encode_into(data, &mut ret);
+        if self.trailing_newline {
+            ret.push(b'\n');
+        }
+        ascii_string(ret)
+    }
+
+    /// Appends the encoding of `data` to `ret`. Padding is only applied to a trailing partial
+    /// group, so callers can encode a stream piecewise in multiples of 5 bytes.
+    fn encode_into<S: ByteSink>(&self, data: &[u8], ret: &mut S) {
+        let alphabet = self.alphabet.table().symbols();
+
+        let chunks = data.chunks_exact(5);
+        let tail = chunks.remainder();
+        for chunk in chunks {
+            ret.push_slice(&encode_chunk(alphabet, chunk, self.bit_order));
+        }
+
+        // Only the symbols the tail needs are pushed, so that `ret` never grows past
+        // `encoded_len` and an exactly sized buffer is not reallocated.
+        if !tail.is_empty() {
+            let len = (tail.len() * 8).div_ceil(5);
+            ret.push_slice(&encode_chunk(alphabet, tail, self.bit_order)[..len]);
+            if self.padding {
+                for _ in len..8 {
+                    ret.push(b'=');
+                }
+            }
+        }
+    }
+
+    /// Strips all trailing padding, returning the remaining data and the number of padding
+    /// characters removed. The count is not capped at the 6 a valid encoding can end with, so
+    /// that an overlong run is reported as padding rather than decoded as symbols.
+    fn strip_padding<'a>(&self, mut data: &'a [u8]) -> (&'a [u8], usize) {
+        if !self.padding {
+            return (data, 0);
+        }
+        let mut padding = 0;
+        loop {
+            if let Some(rest) = data.strip_suffix(b"=") {
+                data = rest;
+            } else if self.percent_padding && (data.ends_with(b"%3D") || data.ends_with(b"%3d")) {
+                data = &data[..data.len() - 3];
+            } else {
+                break;
+            }
+            padding += 1;
+        }
+        (data, padding)
+    }
+
+    #[inline]
+    fn value(&self, c: u8) -> Option<u8> {
+        let values = self.alphabet.table().values();
+        match values[c as usize] {
+            -1 if self.case_insensitive && c.is_ascii_alphabetic() => {
+                match values[(c ^ 0x20) as usize] {
+                    -1 => None,
+                    value => Some(value as u8),
+                }
+            }
+            -1 => None,
+            value => Some(value as u8),
+        }
+    }
+
+    pub fn decode(&self, data: &str) -> Result<Vec<u8>, DecodeError> {
+        self.decode_detailed(data).map(|decoded| decoded.data)
+    }
+
+    /// Decodes `data` like [`decode`](Config::decode), but on failure also returns the bytes
+    /// decoded before the first invalid character.
+    ///
+    /// The prefix holds every byte whose bits come entirely from symbols before the error, so
+    /// `n` symbols give `n * 5 / 8` bytes and a byte split across the invalid symbol is left
+    /// out. Errors not tied to a position, such as [`DecodeError::InvalidLength`], are found
+    /// before any decoding and give an empty prefix.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config, DecodeError};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: false });
+    /// let (prefix, error) = config.decode_partial("MZXW6Y!BOI");
+    /// assert_eq!(prefix, b"foo");
+    /// assert_eq!(error, Some(DecodeError::InvalidChar { index: 6, byte: b'!' }));
+    ///
+    /// assert_eq!(config.decode_partial("MZXW6YTBOI"), (b"foobar".to_vec(), None));
+    /// ```
+    #[must_use]
+    pub fn decode_partial(&self, data: &str) -> (Vec<u8>, Option<DecodeError>) {
+        let error = match self.decode(data) {
+            Ok(decoded) => return (decoded, None),
+            Err(error) => error,
+        };
+        let mut ret = Vec::new();
+        if let Some(index) = error.index() {
+            let symbols = data.as_bytes()[..index]
+                .iter()
+                .copied()
+                .filter(|c| !(self.ignore_whitespace && c.is_ascii_whitespace()))
+                .take_while(|&c| !(self.padding && c == b'='))
+                .collect::<Vec<u8>>();
+            // Every byte before `index` is a symbol, or the error would be earlier.
+            let _ = self.decode_chunks(&symbols, 0, |chunk| ret.extend_from_slice(chunk));
+            ret.truncate(decoded_len(symbols.len()));
+        }
+        (ret, Some(error))
+    }
+
+    /// Returns the canonical form of `data`: the string [`encode`](Config::encode) produces for
+    /// the bytes `data` decodes to. That is, every symbol is the one in the alphabet's table,
+    /// so in its [`Alphabet::canonical_case`] and with Crockford's `I`, `L` and `O` read as `1`,
+    /// `1` and `0`; there is no whitespace; the padding is exactly what this configuration
+    /// writes; and the bits the last symbol carries past the final byte are zero. With a
+    /// [trailing newline](Config::trailing_newline), the canonical form ends in exactly one `\n`.
+    ///
+    /// Input that is already canonical is borrowed without allocating, so keys can be
+    /// normalized on a hot path at the cost of a scan.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    /// use std::borrow::Cow;
+    ///
+    /// let config = Config::new(Alphabet::Crockford);
+    /// assert!(matches!(config.normalize("CSQPYRK1E8"), Ok(Cow::Borrowed("CSQPYRK1E8"))));
+    /// assert_eq!(config.normalize("csqpyrkie8").unwrap(), "CSQPYRK1E8");
+    /// ```
+    pub fn normalize<'a>(&self, data: &'a str) -> Result<Cow<'a, str>, DecodeError> {
+        if self.is_canonical(data.as_bytes()) {
+            return Ok(Cow::Borrowed(data));
+        }
+        self.decode(data)
+            .map(|decoded| Cow::Owned(self.encode(&decoded)))
+    }
+
+    /// Decodes `data` and returns the bytes together with their canonical encoding, as
+    /// [`normalize`](Config::normalize) defines it, so a normalization pipeline gets both from
+    /// one call and they always agree.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: true }).case_insensitive(true);
+    /// let (bytes, canonical) = config.decode_and_canonicalize("mzxw6").unwrap();
+    /// assert_eq!(bytes, b"foo");
+    /// assert_eq!(canonical, "MZXW6===");
+    /// ```
+    pub fn decode_and_canonicalize(&self, data: &str) -> Result<(Vec<u8>, String), DecodeError> {
+        let decoded = self.decode(data)?;
+        let canonical = if self.is_canonical(data.as_bytes()) {
+            String::from(data)
+        } else {
+            self.encode(&decoded)
+        };
+        Ok((decoded, canonical))
+    }
+
+    /// Whether `data` is exactly what [`encode`](Config::encode) would produce for some input,
+    /// without decoding it.
+    fn is_canonical(&self, data: &[u8]) -> bool {
+        let data = match data.strip_suffix(b"\n") {
+            Some(data) if self.trailing_newline => data,
+            _ if self.trailing_newline => return false,
+            _ => data,
+        };
+        let (symbols, padding) = self.strip_padding(data);
+        let len = symbols.len();
+        // A percent-encoded `=` is accepted, but never written.
+        if len + padding != data.len() || self.check_length(len).is_err() {
+            return false;
+        }
+        if self.padding && padding != (8 - len % 8) % 8 {
+            return false;
+        }
+        let table = self.alphabet.table().symbols();
+        let mut last = 0;
+        for &c in symbols {
+            match self.value(c) {
+                Some(value) if table[value as usize] == c => last = value,
+                _ => return false,
+            }
+        }
+        let unused = len % 8 * 5 % 8;
+        match self.bit_order {
+            BitOrder::MsbFirst => last & ((1 << unused) - 1) == 0,
+            BitOrder::LsbFirst => last >> (5 - unused) == 0,
+        }
+    }
+
+    /// Decodes `data` like [`decode`](Config::decode), additionally reporting how much padding
+    /// the input carried compared to its canonical form.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    ///
+    /// let config = Config::new(Alphabet::Rfc4648 { padding: true });
+    /// let decoded = config.decode_detailed("MZXW6=").unwrap();
+    /// assert_eq!(decoded.data, b"foo");
+    /// assert_eq!(decoded.expected_padding, 3);
+    /// assert_eq!(decoded.actual_padding, 1);
+    /// ```
+    pub fn decode_detailed(&self, data: &str) -> Result<Decoded, DecodeError> {
+        let data = match data.strip_suffix('\n') {
+            Some(stripped) if self.trailing_newline => stripped,
+            _ => data,
+        };
+        if self.ignore_whitespace {
+            self.decode_skipping(data.as_bytes(), u8::is_ascii_whitespace)
+        } else {
+            self.decode_bytes(data.as_bytes())
+        }
+    }
+
+    /// Decodes `data` with every byte for which `skip` returns true removed. Error indices
+    /// refer to positions in `data`.
+    fn decode_skipping<F: Fn(&u8) -> bool>(
+        &self,
+        data: &[u8],
+        skip: F,
+    ) -> Result<Decoded, DecodeError> {
+        if !data.iter().any(&skip) {
+            return self.decode_bytes(data);
+        }
+        let filtered = data
+            .iter()
+            .copied()
+            .filter(|c| !skip(c))
+            .collect::<Vec<u8>>();
+        self.decode_bytes(&filtered).map_err(|err| {
+            err.map_index(|index| {
+                data.iter()
+                    .enumerate()
+                    .filter(|&(_, c)| !skip(c))
+                    .nth(index)
+                    .map_or(data.len(), |(i, _)| i)
+            })
+        })
+    }
+
+    /// Decodes `data`, which has had its trailing padding stripped, passing each group of 5
+    /// bytes to `emit`. The last group is zero-filled past the end of the input. `offset` is
+    /// added to the index reported for an invalid character.
+    #[inline]
+    fn decode_chunks<F: FnMut(&[u8; 5])>(
+        &self,
+        data: &[u8],
+        offset: usize,
+        mut emit: F,
+    ) -> Result<(), DecodeError> {
+        for (chunk_index, chunk) in data.chunks(8).enumerate() {
+            let buf = {
+                let mut buf = [0u8; 8];
+                let n = chunk.len();
+                buf[..n].copy_from_slice(chunk);
+                for (i, symbol) in buf[..n].iter_mut().enumerate() {
+                    let c = *symbol;
+                    if self.padding && c == b'=' {
+                        *symbol = 0;
+                        continue;
+                    }
+                    match self.value(c) {
+                        None if !c.is_ascii() => {
+                            return Err(DecodeError::NonAscii {
+                                index: offset + chunk_index * 8 + i,
+                            })
+                        }
+                        None => return Err(self.invalid_char(offset + chunk_index * 8 + i, c)),
+                        Some(value) => *symbol = value,
+                    };
+                }
+                buf
+            };
+            emit(&decode_chunk(&buf, self.bit_order));
+        }
+        Ok(())
+    }
+
+    /// Decodes characters produced lazily, e.g. by a parser, without collecting them into a
+    /// string first. Padding may only appear at the end, and the reported index of an invalid
+    /// or non-ASCII character counts characters rather than bytes.
+    pub fn decode_chars<I: IntoIterator<Item = char>>(
+        &self,
+        chars: I,
+    ) -> Result<Vec<u8>, DecodeError> {
+        let mut ret = Vec::new();
+        let mut buf = [0u8; 8];
+        let mut len = 0;
+        let mut count = 0;
+        let mut padded = false;
+        for (index, c) in chars.into_iter().enumerate() {
+            if self.ignore_whitespace && c.is_ascii_whitespace() {
+                continue;
+            }
+            if !c.is_ascii() {
+                return Err(DecodeError::NonAscii { index });
+            }
+            let byte = c as u8;
+            if self.padding && c == '=' {
+                padded = true;
+                continue;
+            }
+            let value = match self.value(byte) {
+                Some(value) if !padded => value,
+                _ => return Err(self.invalid_char(index, byte)),
+            };
+            buf[len] = value;
+            len += 1;
+            count += 1;
+            if len == 8 {
+                ret.extend_from_slice(&decode_chunk(&buf, self.bit_order));
+                len = 0;
+            }
+        }
+        if len > 0 {
+            for value in &mut buf[len..] {
+                *value = 0;
+            }
+            ret.extend_from_slice(&decode_chunk(&buf, self.bit_order));
+        }
+        self.check_length(count)?;
+        ret.truncate(decoded_len(count));
+        Ok(ret)
+    }
+
+    /// The error for `byte` at `index` not being a symbol. Digits outside `2`-`7` in RFC4648
+    /// input get their own variant, as they usually mean the wrong alphabet was picked.
+    /// Rejects a caller-chosen byte, such as a fill byte or a separator, that is not ASCII or
+    /// is a symbol or the padding, as it could then not be told apart from the data.
+    fn check_reserved(&self, byte: u8) -> Result<(), Base32Error> {
+        if byte.is_ascii() && self.value(byte).is_none() && !(self.padding && byte == b'=') {
+            Ok(())
+        } else {
+            Err(Base32Error::ConflictingByte { byte })
+        }
+    }
+
+    fn invalid_char(&self, index: usize, byte: u8) -> DecodeError {
+        match (self.alphabet, byte) {
+            (Alphabet::Rfc4648 { .. }, b'0' | b'1' | b'8' | b'9')
+            | (Alphabet::Rfc4648Lower { .. }, b'0' | b'1' | b'8' | b'9') => {
+                DecodeError::InvalidDigit { index, byte }
+            }
+            (Alphabet::Crockford, b'U' | b'u') if self.excluded_symbols => {
+                DecodeError::ExcludedSymbol { index, byte }
+            }
+            _ => DecodeError::InvalidChar { index, byte },
+        }
+    }
+
+    /// Rejects symbol counts that no encoder produces: 1, 3 or 6 modulo 8 leave 5 or more
+    /// bits over after the last whole byte. z-base-32 and Bech32 are checked, as their specs
+    /// define the encoded length in terms of whole bits, and so is unpadded RFC4648, where
+    /// the length alone determines the byte count. Padded input keeps inferring missing
+    /// padding unless [`strict_padding`](Config::strict_padding) is set, and Crockford is
+    /// otherwise left lenient.
+    fn check_length(&self, length: usize) -> Result<(), DecodeError> {
+        let strict = (!self.padding && self.alphabet.is_rfc4648())
+            || (self.padding && self.strict_padding);
+        if strict && matches!(length % 8, 1 | 3 | 6) {
+            return Err(DecodeError::InvalidLength { length });
+        }
+        match (self.alphabet, length % 8) {
+            #[cfg(feature = "zbase32")]
+            (Alphabet::Z, 1 | 3 | 6) => Err(DecodeError::InvalidLength { length }),
+            #[cfg(feature = "bech32")]
+            (Alphabet::Bech32, 1 | 3 | 6) => Err(DecodeError::InvalidLength { length }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decodes `data` like [`decode`](Config::decode), keeping the output inline instead of
+    /// allocating when it is at most `N` bytes, e.g. for keys and IDs.
+    ///
+    /// ```
+    /// use base32::{Alphabet, Config};
+    ///
+    /// let config = Config::new(Alphabet::Crockford);
+    /// let key = config.decode_small::<32>("CSQPYRK1E8").unwrap();
+    /// assert_eq!(&*key, b"foobar");
+    /// assert!(!key.spilled());
+    /// ```
+    pub fn decode_small<const N: usize>(&self, data: &str) -> Result<SmallBytes<N>, DecodeError> {
+        if self.ignore_whitespace && data.bytes().any(|c| c.is_ascii_whitespace()) {
+            return self.decode(data).map(SmallBytes::from_vec);
+        }
+        let (body, _, _) = self.unpad(data.as_bytes())?;
+        let output_length = decoded_len(body.len());
+        if output_length > N {
+            return self.decode(data).map(SmallBytes::from_vec);
+        }
+        let mut buf = [0u8; N];
+        let mut len = 0;
+        self.decode_chunks(body, 0, |chunk| {
+            let n = chunk.len().min(output_length - len);
+            buf[len..len + n].copy_from_slice(&chunk[..n]);
+            len += n;
+        })?;
+        Ok(SmallBytes::inline(buf, output_length))
+    }
+
+    /// Strips and validates the padding of `data`, returning the remaining symbols along with
+    /// the expected and actual amount of padding.
+    fn unpad<'a>(&self, data: &'a [u8]) -> Result<(&'a [u8], usize, usize), DecodeError> {
+        let (data, actual_padding) = self.strip_padding(data);
+        self.check_length(data.len())?;
+        let expected_padding = if self.padding {
+            (8 - data.len() % 8) % 8
+        } else {
+            0
+        };
+        if self.strict_padding && actual_padding != expected_padding {
+            return Err(DecodeError::InvalidPadding {
+                expected: expected_padding,
+                actual: actual_padding,
+            });
+        }
+        Ok((data, expected_padding, actual_padding))
+    }
+
+    fn decode_bytes(&self, data: &[u8]) -> Result<Decoded, DecodeError> {
+        let mut ret = Vec::new();
+        let (expected_padding, actual_padding) = self.decode_append(data, &mut ret)?;
+        Ok(Decoded {
+            data: ret,
+            expected_padding,
+            actual_padding,
+        })
+    }
+
+    /// Decodes `data` onto the end of `ret`, returning the expected and actual amount of
+    /// padding. On error `ret` may hold part of the output.
+    fn decode_append(&self, data: &[u8], ret: &mut Vec<u8>) -> Result<(usize, usize), DecodeError> {
+        let (data, expected_padding, actual_padding) = self.unpad(data)?;
+        let output_length = ret.len() + decoded_len(data.len());
+        ret.reserve(decoded_len(data.len()).div_ceil(5) * 5);
+        self.decode_chunks(data, 0, |chunk| ret.extend_from_slice(chunk))?;
+        ret.truncate(output_length);
+        Ok((expected_padding, actual_padding))
+    }
+}
+
+/// Splits 5 bytes into 8 5-bit values (0 to 31), most significant bits first. This is the
+/// bit-level half of RFC4648 encoding; looking each value up in an alphabet gives the symbols.
+///
+/// ```
+/// use base32::{spread_bits, symbol, Alphabet};
+///
+/// let values = spread_bits([0xF8, 0x3E, 0x7F, 0x83, 0xE7]);
+/// assert_eq!(values, [31, 0, 31, 7, 31, 0, 31, 7]);
+///
+/// let symbols = values.map(|v| symbol(Alphabet::Rfc4648 { padding: false }, v).unwrap());
+/// assert_eq!(&symbols, b"7A7H7A7H");
+/// ```
+#[inline]
+pub fn spread_bits(input: [u8; 5]) -> [u8; 8] {
+    let [a, b, c, d, e] = input;
+    radix::spread::<5>(u64::from_be_bytes([0, 0, 0, a, b, c, d, e]))
+}
+
+/// Joins 8 5-bit values (0 to 31), most significant bits first, into 5 bytes: the inverse of
+/// [`spread_bits`]. Values of 32 or more give an unspecified result.
+///
+/// ```
+/// use base32::pack_bits;
+///
+/// assert_eq!(pack_bits([31, 0, 31, 7, 31, 0, 31, 7]), [0xF8, 0x3E, 0x7F, 0x83, 0xE7]);
+/// ```
+#[inline]
+pub fn pack_bits(input: [u8; 8]) -> [u8; 5] {
+    let [_, _, _, a, b, c, d, e] = radix::pack::<5>(input).to_be_bytes();
+    [a, b, c, d, e]
+}
+
+/// Encodes up to 5 bytes into 8 symbols, treating missing bytes as zero.
+#[inline]
+fn encode_chunk(alphabet: &[u8; 32], chunk: &[u8], bit_order: BitOrder) -> [u8; 8] {
+    let mut buf = [0u8; 5];
+    let n = chunk.len();
+    buf[..n].copy_from_slice(chunk);
+    let values = match bit_order {
+        BitOrder::MsbFirst => spread_bits(buf),
+        BitOrder::LsbFirst => {
+            let bits = u64::from_le_bytes([buf[0], buf[1], buf[2], buf[3], buf[4], 0, 0, 0]);
+            let mut ret = [0u8; 8];
+            for (i, value) in ret.iter_mut().enumerate() {
+                *value = (bits >> (5 * i)) as u8 & 0x1F;
+            }
+            ret
+        }
+    };
+    values.map(|value| alphabet[value as usize])
+}
+
+/// The number of symbols encoding `len` bytes without padding, computed without overflowing
+/// for any length a slice can have, and saturating at `usize::MAX` beyond that.
+fn unpadded_len(len: usize) -> usize {
+    (len / INPUT_CHUNK)
+        .saturating_mul(OUTPUT_CHUNK)
+        .saturating_add((len % INPUT_CHUNK * 8).div_ceil(5))
+}
+
+/// The number of bytes `symbols` unpadded symbols decode to, computed without overflowing.
+fn decoded_len(symbols: usize) -> usize {
+    symbols / OUTPUT_CHUNK * INPUT_CHUNK + symbols % OUTPUT_CHUNK * 5 / 8
+}
+
+/// Converts encoder output to a `String`. The symbols of every alphabet are ASCII, so the
+/// fallback is never taken; it only exists so that encoding has no panicking path.
+fn ascii_string(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes)
+        .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned())
+}
+
+/// Packs 8 5-bit values into 5 bytes.
+#[inline]
+fn decode_chunk(buf: &[u8; 8], bit_order: BitOrder) -> [u8; 5] {
+    match bit_order {
+        BitOrder::MsbFirst => pack_bits(*buf),
+        BitOrder::LsbFirst => {
+            let bits = buf
+                .iter()
+                .enumerate()
+                .fold(0u64, |bits, (i, &value)| bits | (value as u64) << (5 * i));
+            let bytes = bits.to_le_bytes();
+            [bytes[0], bytes[1], bytes[2], bytes[3], bytes[4]]
+        }
+    }
+}
+
+pub fn encode(alphabet: Alphabet, data: &[u8]) -> String {
+    Config::new(alphabet).encode(data)
+}
+
+/// Encodes `data` without padding, then fills it up to a multiple of 8 characters with `fill`,
+/// for fixed-width records that need an unpadded alphabet such as Crockford's aligned to whole
+/// groups. A padded alphabet is treated as unpadded, so `fill` replaces its `=`. Decode the
+/// result with [`decode_block_aligned`].
+///
+/// `fill` must be an ASCII byte that is not a symbol of `alphabet`, otherwise
+/// [`Base32Error::ConflictingByte`] is returned. A symbol cannot be used: with `0` as the fill
+/// for Crockford, `CSQPY000` could be 5 symbols and 3 fill bytes or 7 symbols and 1 fill byte,
+/// which decode to 3 and 4 bytes, so the fill could not be stripped reliably.
+///
+/// ```
+/// use base32::{decode_block_aligned, encode_block_aligned, Alphabet, Base32Error};
+///
+/// let encoded = encode_block_aligned(Alphabet::Crockford, b"foo", b'*').unwrap();
+/// assert_eq!(encoded, "CSQPY***");
+/// assert_eq!(decode_block_aligned(Alphabet::Crockford, &encoded, b'*').unwrap(), b"foo");
+/// assert_eq!(
+///     encode_block_aligned(Alphabet::Crockford, b"foo", b'0'),
+///     Err(Base32Error::ConflictingByte { byte: b'0' })
+/// );
+/// ```
+pub fn encode_block_aligned(
+    alphabet: Alphabet,
+    data: &[u8],
+    fill: u8,
+) -> Result<String, Base32Error> {
+    let config = Config::new(alphabet).padding(false);
+    config.check_reserved(fill)?;
+    let mut ret = Vec::with_capacity(data.len().div_ceil(INPUT_CHUNK) * OUTPUT_CHUNK);
+    config.encode_into(data, &mut ret);
+    ret.resize(ret.len().div_ceil(OUTPUT_CHUNK) * OUTPUT_CHUNK, fill);
+    Ok(ascii_string(ret))
 }
 
-const CROCKFORD: &'static [u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
-const RFC4648: &'static [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
-const RFC4648_LOWER: &'static [u8] = b"abcdefghijklmnopqrstuvwxyz234567";
-const RFC4648_HEX: &'static [u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
-const RFC4648_HEX_LOWER: &'static [u8] = b"0123456789abcdefghijklmnopqrstuv";
-const Z: &'static [u8] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+/// Decodes the output of [`encode_block_aligned`]. `data` must be a multiple of 8 characters
+/// long, otherwise [`DecodeError::InvalidLength`] is returned with its length. Up to 7 trailing
+/// `fill` bytes are removed and the rest is decoded as unpadded `alphabet`; a `fill` byte
+/// anywhere else is an invalid character. Decoding errors are returned as
+/// [`Base32Error::Decode`], and a `fill` that [`encode_block_aligned`] would reject as
+/// [`Base32Error::ConflictingByte`].
+pub fn decode_block_aligned(
+    alphabet: Alphabet,
+    data: &str,
+    fill: u8,
+) -> Result<Vec<u8>, Base32Error> {
+    let config = Config::new(alphabet).padding(false);
+    config.check_reserved(fill)?;
+    if !data.len().is_multiple_of(OUTPUT_CHUNK) {
+        return Err(DecodeError::InvalidLength { length: data.len() }.into());
+    }
+    let mut body = data.as_bytes();
+    for _ in 0..OUTPUT_CHUNK - 1 {
+        match body.split_last() {
+            Some((&last, rest)) if last == fill => body = rest,
+            _ => break,
+        }
+    }
+    Ok(config.decode_bytes(body)?.data)
+}
+
+/// Encodes `data` with its letters in uppercase if `uppercase` is set, otherwise lowercase,
+/// whatever case `alphabet` itself uses. Decode the result with
+/// [`Config::case_insensitive`] unless it is in the alphabet's [`Alphabet::canonical_case`];
+/// Crockford accepts either case as it is.
+///
+/// A custom alphabet whose symbols differ only by case cannot be recased and decoded back.
+///
+/// ```
+/// use base32::{encode_cased, Alphabet};
+///
+/// assert_eq!(encode_cased(Alphabet::Crockford, b"foobar", false), "csqpyrk1e8");
+/// assert_eq!(encode_cased(Alphabet::Rfc4648Lower { padding: true }, b"f", true), "MY======");
+/// ```
+pub fn encode_cased(alphabet: Alphabet, data: &[u8], uppercase: bool) -> String {
+    let mut ret = encode(alphabet, data);
+    if uppercase {
+        ret.make_ascii_uppercase();
+    } else {
+        ret.make_ascii_lowercase();
+    }
+    ret
+}
+
+/// Encodes a fixed-size array, such as a key or hash, into a string allocated at exactly the
+/// encoded length. The output is the same as [`encode`], which remains the general path.
+///
+/// ```
+/// use base32::{encode_array, Alphabet};
+///
+/// let key = [0xF8, 0x3E, 0x7F, 0x83, 0xE7];
+/// assert_eq!(encode_array(Alphabet::Rfc4648 { padding: true }, &key), "7A7H7A7H");
+/// ```
+pub fn encode_array<const N: usize>(alphabet: Alphabet, data: &[u8; N]) -> String {
+    let config = Config::new(alphabet);
+    let mut ret = Vec::with_capacity(config.encoded_len(N));
+    config.encode_into(data, &mut ret);
+    ascii_string(ret)
+}
+
+/// Encodes a 32-byte hash, such as a SHA-256 digest, for content addressing: 52 characters
+/// for the unpadded alphabets and 56 for padded RFC4648. The output is the same as
+/// [`encode_array`].
+///
+/// ```
+/// use base32::{decode_hash32, encode_hash32, Alphabet};
+///
+/// let hash = [0xA5; 32];
+/// let encoded = encode_hash32(Alphabet::Crockford, &hash);
+/// assert_eq!(encoded.len(), 52);
+/// assert_eq!(decode_hash32(Alphabet::Crockford, &encoded).unwrap(), hash);
+/// ```
+pub fn encode_hash32(alphabet: Alphabet, hash: &[u8; 32]) -> String {
+    encode_array(alphabet, hash)
+}
+
+/// Decodes the output of [`encode_hash32`] into an array on the stack. `data` must be exactly
+/// as long as the encoding of 32 bytes, padding included, or
+/// [`DecodeError::UnexpectedLength`] is returned with its length in characters. Input of the
+/// right length whose padding hides some of the symbols fails the same way with the number of
+/// bytes it decodes to.
+pub fn decode_hash32(alphabet: Alphabet, data: &str) -> Result<[u8; 32], DecodeError> {
+    let config = Config::new(alphabet);
+    let expected = config.encoded_len(32);
+    if data.len() != expected {
+        return Err(DecodeError::UnexpectedLength {
+            got: data.len(),
+            expected,
+        });
+    }
+    let decoded = config.decode_small::<32>(data)?;
+    if decoded.len() != 32 {
+        return Err(DecodeError::UnexpectedLength {
+            got: decoded.len(),
+            expected: 32,
+        });
+    }
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&decoded);
+    Ok(hash)
+}
+
+/// Encodes `data` followed by a single `\n`, matching the output of the coreutils `base32` tool
+/// for short inputs. The newline is the only difference from [`encode`]; long output is not
+/// wrapped. [`decode`] does not skip the newline, so read it back with
+/// [`Config::trailing_newline`] or [`Config::ignore_whitespace`], or trim it first.
+///
+/// ```
+/// use base32::Alphabet;
+///
+/// assert_eq!(base32::encode_line(Alphabet::Rfc4648 { padding: true }, b"foo"), "MZXW6===\n");
+/// ```
+pub fn encode_line(alphabet: Alphabet, data: &[u8]) -> String {
+    Config::new(alphabet).trailing_newline(true).encode(data)
+}
+
+/// The longest DNS label, in bytes.
+const DNS_LABEL_LEN: usize = 63;
+
+/// Encodes `data` as DNS labels: lowercase, unpadded, and split into segments of 63 characters,
+/// the longest a label may be, with only the last one shorter. Join them with `.` to form a
+/// name; mind the 253 character limit on whole names. Empty `data` gives no labels.
+///
+/// DNS compares labels case-insensitively and resolvers may change their case, so the symbols
+/// are lowercased whatever case `alphabet` uses. A custom alphabet whose symbols differ only
+/// by case cannot be used. Decode the labels with [`decode_dns_labels`].
+///
+/// ```
+/// use base32::{encode_dns_labels, Alphabet};
+///
+/// let labels = encode_dns_labels(Alphabet::Rfc4648 { padding: true }, &[0xA5; 40]);
+/// assert_eq!(labels.len(), 2);
+/// assert_eq!(labels[0].len(), 63);
+/// assert_eq!(labels[1], "f");
+/// ```
+pub fn encode_dns_labels(alphabet: Alphabet, data: &[u8]) -> Vec<String> {
+    let mut encoded = Config::new(alphabet).padding(false).encode(data);
+    encoded.make_ascii_lowercase();
+    encoded
+        .as_bytes()
+        .chunks(DNS_LABEL_LEN)
+        .map(|label| ascii_string(label.to_vec()))
+        .collect()
+}
+
+/// Decodes labels made by [`encode_dns_labels`], e.g. `name.split('.')` without the zone
+/// suffix.
+///
+/// The labels are concatenated in order and decoded as one unpadded, case-insensitive string,
+/// so they need not split where the encoder split them, and empty labels, such as the one after
+/// a trailing `.`, are ignored. Error indices count characters across all labels, as if they
+/// had been joined without separators.
+///
+/// ```
+/// use base32::{decode_dns_labels, encode_dns_labels, Alphabet};
+///
+/// let alphabet = Alphabet::Rfc4648 { padding: true };
+/// let name = encode_dns_labels(alphabet, b"foobar").join(".");
+/// assert_eq!(name, "mzxw6ytboi");
+/// assert_eq!(decode_dns_labels(alphabet, "MZXW6.YTBOI.".split('.')).unwrap(), b"foobar");
+/// ```
+pub fn decode_dns_labels<'a, I: IntoIterator<Item = &'a str>>(
+    alphabet: Alphabet,
+    labels: I,
+) -> Result<Vec<u8>, DecodeError> {
+    let joined = labels.into_iter().collect::<String>();
+    Config::new(alphabet)
+        .padding(false)
+        .case_insensitive(true)
+        .decode(&joined)
+}
+
+/// Encodes the concatenation of `chunks`, such as the buffers of a scatter-gather read, without
+/// first copying them into one contiguous buffer. A group of 5 bytes split across chunk
+/// boundaries is carried over, so the output is the same as [`encode`] of the joined bytes.
+///
+/// ```
+/// use base32::{encode, encode_chunks, Alphabet};
+///
+/// let alphabet = Alphabet::Rfc4648 { padding: true };
+/// let chunks: [&[u8]; 3] = [b"f", b"ooba", b"r"];
+/// assert_eq!(encode_chunks(alphabet, chunks), encode(alphabet, b"foobar"));
+/// ```
+pub fn encode_chunks<'a, I: IntoIterator<Item = &'a [u8]>>(
+    alphabet: Alphabet,
+    chunks: I,
+) -> String {
+    let config = Config::new(alphabet);
+    let mut ret = Vec::new();
+    let mut pending = [0u8; INPUT_CHUNK];
+    let mut pending_len = 0;
+    for mut chunk in chunks {
+        if pending_len > 0 {
+            let n = (INPUT_CHUNK - pending_len).min(chunk.len());
+            pending[pending_len..pending_len + n].copy_from_slice(&chunk[..n]);
+            pending_len += n;
+            chunk = &chunk[n..];
+            if pending_len < INPUT_CHUNK {
+                continue;
+            }
+            config.encode_into(&pending, &mut ret);
+        }
+        let whole = chunk.len() - chunk.len() % INPUT_CHUNK;
+        config.encode_into(&chunk[..whole], &mut ret);
+        pending_len = chunk.len() - whole;
+        pending[..pending_len].copy_from_slice(&chunk[whole..]);
+    }
+    config.encode_into(&pending[..pending_len], &mut ret);
+    ascii_string(ret)
+}
+
+/// Decodes `data`, returning `None` if it is not valid in `alphabet`. Use [`Config::decode`]
+/// to find out what is wrong with it.
+///
+/// Ignoring the result is almost always a bug, such as validating input and then using it
+/// regardless, so it is `#[must_use]`:
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+///
+/// base32::decode(base32::Alphabet::Crockford, "CSQPYRK1E8");
+/// ```
+#[must_use]
+pub fn decode(alphabet: Alphabet, data: &str) -> Option<Vec<u8>> {
+    Config::new(alphabet).decode(data).ok()
+}
+
+/// Decodes `data` like [`Config::decode`], but without a branch per symbol on its validity.
+///
+/// [`decode`] checks every symbol as it goes and stops at the first invalid one. This function
+/// looks up all symbols first, folding a flag for any invalid one into a single value, and
+/// only checks the flag at the end. That avoids a hard to predict branch in the inner loop,
+/// which pays off when nearly all input is valid, e.g. tokens already produced by this crate.
+/// When the flag is set the input is decoded again with [`Config::decode`] to find and report
+/// the error, so invalid input costs a full pass more than with [`decode`]; use that for input
+/// that is often invalid, such as unvalidated user input. `cargo bench --bench throughput`
+/// measures both cases.
+///
+/// The result, including any error, is always the same as that of [`Config::decode`].
+///
+/// ```
+/// use base32::{decode_branchless, Alphabet, DecodeError};
+///
+/// assert_eq!(decode_branchless(Alphabet::Crockford, "CSQPYRK1E8").unwrap(), b"foobar");
+/// assert_eq!(
+///     decode_branchless(Alphabet::Crockford, "CSQPYRK1EU"),
+///     Err(DecodeError::InvalidChar { index: 9, byte: b'U' })
+/// );
+/// ```
+pub fn decode_branchless(alphabet: Alphabet, data: &str) -> Result<Vec<u8>, DecodeError> {
+    let config = Config::new(alphabet);
+    let (symbols, _, _) = config.unpad(data.as_bytes())?;
+    let values = alphabet.table().values();
+    let mut ret = Vec::with_capacity(decoded_len(symbols.len()).div_ceil(5) * 5);
+    // Every value is below 32 and `-1` sets the top bit, so the OR of all of them has the top
+    // bit set exactly when some symbol is invalid.
+    let mut invalid = 0u8;
+    for chunk in symbols.chunks(8) {
+        let mut buf = [0u8; 8];
+        for (value, &c) in buf.iter_mut().zip(chunk) {
+            *value = values[c as usize] as u8;
+            invalid |= *value;
+        }
+        // Invalid symbols decode to garbage here, which is discarded below.
+        ret.extend_from_slice(&pack_bits(buf.map(|value| value & 0x1F)));
+    }
+    if invalid & 0x80 != 0 {
+        return config.decode(data);
+    }
+    ret.truncate(decoded_len(symbols.len()));
+    Ok(ret)
+}
+
+/// Decodes RFC4648 base32 whether or not it is padded, so callers need not pick between
+/// `Rfc4648 { padding: true }` and `Rfc4648 { padding: false }`. The choice only affects
+/// encoding; when decoding, input ending in `=` is read as padded and anything else as
+/// unpadded, which rejects lengths no encoder produces.
+///
+/// ```
+/// use base32::decode_rfc4648_auto;
+///
+/// assert_eq!(decode_rfc4648_auto("MZXW6===").unwrap(), b"foo");
+/// assert_eq!(decode_rfc4648_auto("MZXW6").unwrap(), b"foo");
+/// ```
+pub fn decode_rfc4648_auto(data: &str) -> Result<Vec<u8>, DecodeError> {
+    let padding = data.ends_with('=');
+    Config::new(Alphabet::Rfc4648 { padding }).decode(data)
+}
+
+/// Decodes text typed or pasted by a person, such as a code entered in a form, accepting the
+/// common ways it differs from what was printed. Exactly these leniencies apply:
+///
+/// - letters are accepted in either case ([`Config::case_insensitive`]);
+/// - ASCII whitespace and `-` are skipped anywhere, so grouped codes like `CSQP-YRK1` decode
+///   ([`Config::ignore_whitespace`]);
+/// - for the RFC4648 alphabets, trailing `=` padding may be present, missing or wrong,
+///   whatever `alphabet` says. Input without any is checked like unpadded input, so lengths
+///   no encoder produces are rejected as in [`decode_rfc4648_auto`]. A `=` before the
+///   trailing run, or in any other alphabet, is an invalid character;
+/// - for Crockford, `I` and `L` are read as `1` and `O` as `0`, as [`decode`] always does.
+///
+/// Whitespace and `-` are only skipped if they are not symbols of `alphabet`. Everything else,
+/// including the length checks of [`Config::decode`], is as strict as usual, and error
+/// indices refer to positions in `data`. Input with nothing but skipped characters and
+/// padding, such as `"----"`, decodes to an empty `Vec`.
+///
+/// ```
+/// use base32::{decode_friendly, Alphabet};
+///
+/// assert_eq!(decode_friendly(Alphabet::Crockford, " csqp-yrki-e8\n").unwrap(), b"foobar");
+/// assert_eq!(decode_friendly(Alphabet::Rfc4648 { padding: true }, "mzxw-6").unwrap(), b"foo");
+/// ```
+pub fn decode_friendly(alphabet: Alphabet, data: &str) -> Result<Vec<u8>, DecodeError> {
+    let config = Config::new(alphabet).case_insensitive(true);
+    let skip = |c: &u8| (c.is_ascii_whitespace() || *c == b'-') && config.value(*c).is_none();
+    let data = data.as_bytes();
+    // Padding is the run of `=` after the last symbol, with skipped characters allowed within
+    // and after it. Without padding, the decoder rejects every `=` by itself.
+    let padding = alphabet.is_rfc4648() && data.iter().rfind(|c| !skip(c)) == Some(&b'=');
+    if padding {
+        let end = data
+            .iter()
+            .rposition(|c| *c != b'=' && !skip(c))
+            .map_or(0, |i| i + 1);
+        if let Some(index) = data[..end].iter().position(|&c| c == b'=') {
+            return Err(DecodeError::InvalidChar { index, byte: b'=' });
+        }
+    }
+    config
+        .padding(padding)
+        .decode_skipping(data, skip)
+        .map(|decoded| decoded.data)
+}
+
+/// Decodes `data`, returning the bytes decoded before the first error along with the error.
+/// See [`Config::decode_partial`] for how much of the input the prefix covers.
+#[must_use]
+pub fn decode_partial(alphabet: Alphabet, data: &str) -> (Vec<u8>, Option<DecodeError>) {
+    Config::new(alphabet).decode_partial(data)
+}
+
+/// Returns the canonical form of `data` as defined by [`Config::normalize`], borrowing it if it
+/// is already canonical, or `None` if it does not decode.
+///
+/// ```
+/// use base32::{normalize, Alphabet};
+/// use std::borrow::Cow;
+///
+/// let alphabet = Alphabet::Rfc4648 { padding: true };
+/// assert!(matches!(normalize(alphabet, "MZXQ===="), Some(Cow::Borrowed(_))));
+/// // The last symbol carries bits past the end of the byte.
+/// assert_eq!(normalize(alphabet, "MZXR====").unwrap(), "MZXQ====");
+/// ```
+pub fn normalize(alphabet: Alphabet, data: &str) -> Option<Cow<'_, str>> {
+    Config::new(alphabet).normalize(data).ok()
+}
+
+/// Decodes `data` and returns the bytes along with their canonical encoding. See
+/// [`Config::decode_and_canonicalize`].
+///
+/// ```
+/// use base32::{decode_and_canonicalize, Alphabet};
+///
+/// let (bytes, canonical) = decode_and_canonicalize(Alphabet::Crockford, "csqpyrkie8").unwrap();
+/// assert_eq!(bytes, b"foobar");
+/// assert_eq!(canonical, "CSQPYRK1E8");
+/// ```
+pub fn decode_and_canonicalize(
+    alphabet: Alphabet,
+    data: &str,
+) -> Result<(Vec<u8>, String), DecodeError> {
+    Config::new(alphabet).decode_and_canonicalize(data)
+}
+
+/// Returns the symbol `alphabet` uses for the 5-bit `value`, or `None` if `value` is 32 or more.
+///
+/// ```
+/// use base32::{symbol, Alphabet};
+///
+/// assert_eq!(symbol(Alphabet::Crockford, 31), Some(b'Z'));
+/// assert_eq!(symbol(Alphabet::Crockford, 32), None);
+/// ```
+pub fn symbol(alphabet: Alphabet, value: u8) -> Option<u8> {
+    alphabet.table().symbols().get(value as usize).copied()
+}
+
+/// Returns the 5-bit value `symbol` decodes to in `alphabet`, or `None` if it is not a symbol.
+/// Aliases accepted by the decoder, such as Crockford's lowercase letters and `I`/`L`/`O`, map
+/// to the value of the symbol they stand for.
+///
+/// ```
+/// use base32::{value, Alphabet};
+///
+/// assert_eq!(value(Alphabet::Crockford, b'Z'), Some(31));
+/// assert_eq!(value(Alphabet::Crockford, b'l'), Some(1));
+/// assert_eq!(value(Alphabet::Crockford, b'U'), None);
+/// ```
+pub fn value(alphabet: Alphabet, symbol: u8) -> Option<u8> {
+    Config::new(alphabet).value(symbol)
+}
+
+/// Compares two alphabets value by value and returns the symbols `a` and `b` use for each value
+/// where they differ, in order of value. An empty result means the two encode identically.
+///
+/// This explains why text valid in one alphabet fails, or decodes differently, in the other:
+/// each pair is a symbol that means different things. Only the encoding symbols are compared,
+/// not aliases such as Crockford's `I`, `L` and `O` or padding; [`value`] gives the value of
+/// either symbol in a pair.
+///
+/// ```
+/// use base32::{alphabet_diff, Alphabet};
+///
+/// let diff = alphabet_diff(Alphabet::Rfc4648 { padding: true }, Alphabet::Crockford);
+/// assert_eq!(diff.len(), 32);
+/// assert_eq!(diff[0], (b'A', b'0'));
+/// assert!(alphabet_diff(Alphabet::Crockford, Alphabet::Crockford).is_empty());
+/// ```
+pub fn alphabet_diff(a: Alphabet, b: Alphabet) -> Vec<(u8, u8)> {
+    let a = a.table().symbols();
+    let b = b.table().symbols();
+    a.iter()
+        .zip(b)
+        .filter(|(x, y)| x != y)
+        .map(|(&x, &y)| (x, y))
+        .collect()
+}
+
+/// Returns whether `c` is a symbol of `alphabet` in either case, e.g. to validate keystrokes
+/// as they are typed. Crockford's `I`, `L` and `O` count as symbols, `U` does not. The padding
+/// character `=` is never valid.
+///
+/// ```
+/// use base32::{is_valid_char, Alphabet};
+///
+/// assert!(is_valid_char(Alphabet::Rfc4648 { padding: true }, 'q'));
+/// assert!(is_valid_char(Alphabet::Crockford, 'o'));
+/// assert!(!is_valid_char(Alphabet::Crockford, 'U'));
+/// assert!(!is_valid_char(Alphabet::Rfc4648 { padding: true }, '='));
+/// ```
+pub fn is_valid_char(alphabet: Alphabet, c: char) -> bool {
+    c.is_ascii()
+        && Config::new(alphabet)
+            .case_insensitive(true)
+            .value(c as u8)
+            .is_some()
+}
+
+/// The ASCII bytes `alphabet` decodes, as a bit set: bit `c` is set if [`value`] accepts `c`.
+fn symbol_class(alphabet: Alphabet) -> u128 {
+    let config = Config::new(alphabet);
+    (0..128u8)
+        .filter(|&c| config.value(c).is_some())
+        .fold(0, |class, c| class | 1 << c)
+}
+
+/// Returns a regular expression character class matching exactly the characters that
+/// [`decode`] accepts as symbols of `alphabet`, for pre-filtering text with a regex engine.
+/// Padding is not included; append `=*` to the pattern for a padded alphabet.
+///
+/// The class is derived from the decoding table, so it follows its case sensitivity and
+/// includes aliases such as Crockford's `I`, `L` and `O`. Characters are listed in ASCII order
+/// with runs of three or more written as ranges, and `\`, `-`, `[`, `]` and `^` are escaped
+/// with `\`, which every common regex syntax understands.
+///
+/// ```
+/// use base32::{alphabet_regex, Alphabet};
+///
+/// assert_eq!(alphabet_regex(Alphabet::Rfc4648 { padding: true }), "[2-7A-Z]");
+/// assert_eq!(alphabet_regex(Alphabet::Crockford), "[0-9A-TV-Za-tv-z]");
+/// ```
+pub fn alphabet_regex(alphabet: Alphabet) -> String {
+    let class = symbol_class(alphabet);
+    let contains = |c: u8| c < 128 && class >> c & 1 == 1;
+    let push = |ret: &mut String, c: u8| {
+        if matches!(c, b'\\' | b'-' | b'[' | b']' | b'^') {
+            ret.push('\\');
+        }
+        ret.push(c as char);
+    };
+
+    let mut ret = String::from("[");
+    let mut c = 0;
+    while c < 128 {
+        if !contains(c) {
+            c += 1;
+            continue;
+        }
+        let start = c;
+        while contains(c + 1) {
+            c += 1;
+        }
+        push(&mut ret, start);
+        if c - start >= 2 {
+            ret.push('-');
+        }
+        if c != start {
+            push(&mut ret, c);
+        }
+        c += 1;
+    }
+    ret.push(']');
+    ret
+}
+
+/// Decodes `data` without allocating when the output is at most `N` bytes. See
+/// [`Config::decode_small`].
+pub fn decode_small<const N: usize>(
+    alphabet: Alphabet,
+    data: &str,
+) -> Result<SmallBytes<N>, DecodeError> {
+    Config::new(alphabet).decode_small(data)
+}
+
+/// Decodes an iterator of characters. See [`Config::decode_chars`].
+///
+/// ```
+/// use base32::{decode_chars, Alphabet};
+///
+/// let chars = "MZXW6===".chars();
+/// assert_eq!(decode_chars(Alphabet::Rfc4648 { padding: true }, chars).unwrap(), b"foo");
+/// ```
+pub fn decode_chars<I: IntoIterator<Item = char>>(
+    alphabet: Alphabet,
+    chars: I,
+) -> Result<Vec<u8>, DecodeError> {
+    Config::new(alphabet).decode_chars(chars)
+}
+
+/// Decodes `encoded` chunk by chunk, passing each decoded chunk along with the corresponding
+/// part of `bytes` to `compare`. Returns false without calling `compare` if `encoded` is invalid
+/// or decodes to a different length than `bytes`.
+fn compare_decoded<F: FnMut(&[u8], &[u8])>(
+    alphabet: Alphabet,
+    encoded: &str,
+    bytes: &[u8],
+    mut compare: F,
+) -> bool {
+    let config = Config::new(alphabet);
+    let (data, _) = config.strip_padding(encoded.as_bytes());
+    if config.check_length(data.len()).is_err() || decoded_len(data.len()) != bytes.len() {
+        return false;
+    }
+    let mut buf = [0u8; INPUT_CHUNK * 64];
+    for (batch_index, batch) in data.chunks(OUTPUT_CHUNK * 64).enumerate() {
+        let mut len = 0;
+        let valid = config.decode_chunks(batch, 0, |chunk| {
+            buf[len..len + INPUT_CHUNK].copy_from_slice(chunk);
+            len += INPUT_CHUNK;
+        });
+        if valid.is_err() {
+            return false;
+        }
+        let start = batch_index * buf.len();
+        let len = len.min(bytes.len() - start);
+        compare(&buf[..len], &bytes[start..start + len]);
+    }
+    true
+}
+
+/// Returns whether `encoded` is valid in `alphabet` and decodes to exactly `bytes`, without
+/// allocating.
+///
+/// The comparison stops at the first difference, so the time taken reveals how much of
+/// `bytes` was matched. Use [`matches_bytes_constant_time`] to compare secrets.
+///
+/// ```
+/// use base32::{matches_bytes, Alphabet};
+///
+/// assert!(matches_bytes(Alphabet::Crockford, "CSQPYRK1E8", b"foobar"));
+/// assert!(!matches_bytes(Alphabet::Crockford, "CSQPYRK1E8", b"fooba"));
+/// assert!(!matches_bytes(Alphabet::Crockford, "CSQPYRK1EU", b"foobar"));
+/// ```
+pub fn matches_bytes(alphabet: Alphabet, encoded: &str, bytes: &[u8]) -> bool {
+    let mut equal = true;
+    compare_decoded(alphabet, encoded, bytes, |decoded, bytes| {
+        equal = equal && decoded == bytes;
+    }) && equal
+}
+
+/// Like [`matches_bytes`], but the comparison of the decoded data with `bytes` takes the same
+/// time wherever they differ, for checking tokens and other secrets.
+///
+/// Only the comparison is constant time. Lengths are compared up front, and decoding `encoded`
+/// still takes a different path for invalid input, so the length and validity of `encoded`
+/// are not hidden.
+///
+/// ```
+/// use base32::{matches_bytes_constant_time, Alphabet};
+///
+/// assert!(matches_bytes_constant_time(Alphabet::Crockford, "CSQPYRK1E8", b"foobar"));
+/// assert!(!matches_bytes_constant_time(Alphabet::Crockford, "CSQPYRK1E8", b"foobaz"));
+/// ```
+pub fn matches_bytes_constant_time(alphabet: Alphabet, encoded: &str, bytes: &[u8]) -> bool {
+    let mut diff = 0;
+    let valid = compare_decoded(alphabet, encoded, bytes, |decoded, bytes| {
+        for (a, b) in decoded.iter().zip(bytes) {
+            diff |= a ^ b;
+        }
+    });
+    valid & (core::hint::black_box(diff) == 0)
+}
+
+/// Decodes `data`, failing with [`DecodeError::UnexpectedLength`] unless it decodes to exactly
+/// `expected_len` bytes. This guards fixed-size fields such as keys and hashes against
+/// truncated or overlong input.
+///
+/// ```
+/// use base32::{decode_exact, Alphabet, DecodeError};
+///
+/// assert_eq!(decode_exact(Alphabet::Crockford, "CSQPYRK1E8", 6).unwrap(), b"foobar");
+/// assert_eq!(
+///     decode_exact(Alphabet::Crockford, "CSQPYRK1", 6),
+///     Err(DecodeError::UnexpectedLength { got: 5, expected: 6 })
+/// );
+/// ```
+pub fn decode_exact(
+    alphabet: Alphabet,
+    data: &str,
+    expected_len: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    let decoded = Config::new(alphabet).decode(data)?;
+    if decoded.len() != expected_len {
+        return Err(DecodeError::UnexpectedLength {
+            got: decoded.len(),
+            expected: expected_len,
+        });
+    }
+    Ok(decoded)
+}
+
+/// Decodes unpadded `data` that must encode exactly `byte_len` bytes. Padding is never
+/// accepted, whatever `alphabet` says.
+///
+/// Without padding the number of bytes is inferred from the number of symbols, so a producer
+/// that drops or adds trailing symbols silently changes the output length. Here input that
+/// decodes to any other number of bytes fails with [`DecodeError::LengthMismatch`] giving the
+/// actual and required number of bytes. Input that decodes to `byte_len` bytes but is not the
+/// `ceil(8 * byte_len / 5)` symbols an encoder writes for them, such as 3 symbols for 1 byte,
+/// fails with [`DecodeError::InvalidLength`].
+///
+/// ```
+/// use base32::{decode_unpadded_exact, Alphabet, DecodeError};
+///
+/// assert_eq!(decode_unpadded_exact(Alphabet::Crockford, "CSQPYRK1E8", 6).unwrap(), b"foobar");
+/// assert_eq!(
+///     decode_unpadded_exact(Alphabet::Crockford, "CSQPYRK1", 6),
+///     Err(DecodeError::LengthMismatch { got: 5, expected: 6 })
+/// );
+/// ```
+pub fn decode_unpadded_exact(
+    alphabet: Alphabet,
+    data: &str,
+    byte_len: usize,
+) -> Result<Vec<u8>, DecodeError> {
+    let decoded = Config::new(alphabet).padding(false).decode(data)?;
+    if decoded.len() != byte_len {
+        return Err(DecodeError::LengthMismatch {
+            got: decoded.len(),
+            expected: byte_len,
+        });
+    }
+    if data.len() != unpadded_len(byte_len) {
+        return Err(DecodeError::InvalidLength { length: data.len() });
+    }
+    Ok(decoded)
+}
+
+/// Decodes `data` and also returns, for each output byte, the range of input character indices
+/// whose bits it was decoded from, e.g. for highlighting the text behind a byte.
+///
+/// Byte `i` covers bits `8 * i` to `8 * i + 7`, and character `j` carries bits `5 * j` to
+/// `5 * j + 4`, so each byte spans two or three characters and neighbouring spans share a
+/// character. The pattern repeats every 5 bytes and 8 characters:
+///
+/// | byte | 0      | 1      | 2      | 3      | 4      |
+/// |------|--------|--------|--------|--------|--------|
+/// | span | `0..2` | `1..4` | `3..5` | `4..7` | `6..8` |
+///
+/// Padding is never part of a span.
+///
+/// ```
+/// use base32::{decode_with_spans, Alphabet};
+///
+/// let (data, spans) = decode_with_spans(Alphabet::Rfc4648 { padding: true }, "MZXW6===").unwrap();
+/// assert_eq!(data, b"foo");
+/// assert_eq!(spans, [0..2, 1..4, 3..5]);
+/// ```
+pub fn decode_with_spans(
+    alphabet: Alphabet,
+    data: &str,
+) -> Result<(Vec<u8>, Vec<Range<usize>>), DecodeError> {
+    let decoded = Config::new(alphabet).decode(data)?;
+    // Byte `i` starts at bit `8 * i`, split into whole groups first so that it cannot overflow.
+    let spans = (0..decoded.len())
+        .map(|i| {
+            let (group, bit) = (i / INPUT_CHUNK * OUTPUT_CHUNK, i % INPUT_CHUNK * 8);
+            group + bit / 5..group + (bit + 7) / 5 + 1
+        })
+        .collect();
+    Ok((decoded, spans))
+}
+
+/// Splits `data` into blocks of `block_chars` characters, the last one possibly shorter, that
+/// can be decoded independently, e.g. on separate threads. Concatenating the decoded blocks
+/// gives the same bytes as decoding `data` as a whole, for padded and unpadded alphabets alike.
+///
+/// # Panics
+///
+/// Panics if `block_chars` is not a non-zero multiple of 8, as a block boundary inside a group
+/// would split the bits of a byte between two blocks.
+///
+/// ```
+/// use base32::{chunks_decodable, decode, Alphabet};
+///
+/// let alphabet = Alphabet::Rfc4648 { padding: true };
+/// let blocks = chunks_decodable("MZXW6YTBOI======", 8).collect::<Vec<_>>();
+/// assert_eq!(blocks, ["MZXW6YTB", "OI======"]);
+///
+/// let decoded = blocks.iter().flat_map(|block| decode(alphabet, block).unwrap());
+/// assert_eq!(decoded.collect::<Vec<u8>>(), b"foobar");
+/// ```
+pub fn chunks_decodable<'a>(
+    data: &'a str,
+    block_chars: usize,
+) -> impl Iterator<Item = &'a str> + 'a {
+    assert!(
+        block_chars > 0 && block_chars.is_multiple_of(OUTPUT_CHUNK),
+        "block size must be a non-zero multiple of 8"
+    );
+    let mut rest = data;
+    core::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        // Valid input is ASCII; for anything else keep whole characters together and leave
+        // the error to the decoder.
+        let mut end = block_chars.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        let (block, tail) = rest.split_at(end);
+        rest = tail;
+        Some(block)
+    })
+}
+
+/// Decodes `data`, silently skipping every byte in `ignore`, such as the `.` or `:` used as
+/// visual separators in some key formats. Error indices refer to positions in `data`, and
+/// input made only of ignored bytes decodes to an empty `Vec`. Decoding errors are returned
+/// as [`Base32Error::Decode`].
+///
+/// Every byte in `ignore` must be ASCII and neither a byte `alphabet` decodes nor `=` if it is
+/// padded, as the input would otherwise be ambiguous. The first that is not is returned as
+/// [`Base32Error::ConflictingByte`], so `ignore` can come from configuration.
+///
+/// ```
+/// use base32::{decode_ignoring, Alphabet, Base32Error};
+///
+/// let decoded = decode_ignoring(Alphabet::Crockford, "CSQ:PYRK.1E8", b".:").unwrap();
+/// assert_eq!(decoded, b"foobar");
+/// assert_eq!(
+///     decode_ignoring(Alphabet::Crockford, "CSQ-PYRK-1E8", b"-L"),
+///     Err(Base32Error::ConflictingByte { byte: b'L' })
+/// );
+/// ```
+pub fn decode_ignoring(
+    alphabet: Alphabet,
+    data: &str,
+    ignore: &[u8],
+) -> Result<Vec<u8>, Base32Error> {
+    let config = Config::new(alphabet);
+    for &c in ignore {
+        config.check_reserved(c)?;
+    }
+    Ok(config
+        .decode_skipping(data.as_bytes(), |c| ignore.contains(c))?
+        .data)
+}
+
+/// Decodes `data`, calling `f` with the raw byte for every character that is not part of
+/// `alphabet` (or its padding). `f` returns the character's 5-bit value, which must be in
+/// `0..32`, or `None` to reject it; a rejected character, or one mapped outside `0..32`, is
+/// reported like any other invalid character. This extends an alphabet with extra symbols the
+/// way Crockford's alphabet folds `I`, `L` and `O` into `1` and `0`.
+///
+/// ```
+/// use base32::{decode_with_fallback, Alphabet, DecodeError};
+///
+/// // Read the digits 0 and 1 as the letters O and I.
+/// let fallback = |c| match c {
+///     b'0' => Some(14),
+///     b'1' => Some(8),
+///     _ => None,
+/// };
+/// let alphabet = Alphabet::Rfc4648 { padding: false };
+/// assert_eq!(decode_with_fallback(alphabet, "MZXW6YTB0I", fallback).unwrap(), b"foobar");
+/// assert!(matches!(
+///     decode_with_fallback(alphabet, "MZXW6YTB8I", fallback),
+///     Err(DecodeError::InvalidDigit { index: 8, .. })
+/// ));
+/// ```
+pub fn decode_with_fallback<F: Fn(u8) -> Option<u8>>(
+    alphabet: Alphabet,
+    data: &str,
+    f: F,
+) -> Result<Vec<u8>, DecodeError> {
+    let config = Config::new(alphabet);
+    let symbols = alphabet.table().symbols();
+    let mapped = data
+        .bytes()
+        .map(|c| {
+            if config.value(c).is_some() || (config.padding && c == b'=') {
+                return c;
+            }
+            match f(c) {
+                Some(value) if value < 32 => symbols[value as usize],
+                _ => c,
+            }
+        })
+        .collect::<Vec<u8>>();
+    config.decode_bytes(&mapped).map(|decoded| decoded.data)
+}
+
+/// Decodes `data` after removing `prefix` and `suffix`, markers such as the `b32:` some systems
+/// tag their encodings with. Either may be empty. Error indices refer to positions in `data`.
+///
+/// Returns [`DecodeError::MissingPrefix`] or [`DecodeError::MissingSuffix`] if a marker is
+/// absent; the prefix is checked first.
+///
+/// ```
+/// use base32::{decode_trimmed, Alphabet, DecodeError};
+///
+/// let alphabet = Alphabet::Rfc4648 { padding: true };
+/// assert_eq!(decode_trimmed(alphabet, "b32:MZXW6===", "b32:", "").unwrap(), b"foo");
+/// assert_eq!(decode_trimmed(alphabet, "MZXW6===", "b32:", ""), Err(DecodeError::MissingPrefix));
+/// ```
+pub fn decode_trimmed(
+    alphabet: Alphabet,
+    data: &str,
+    prefix: &str,
+    suffix: &str,
+) -> Result<Vec<u8>, DecodeError> {
+    let payload = data
+        .strip_prefix(prefix)
+        .ok_or(DecodeError::MissingPrefix)?
+        .strip_suffix(suffix)
+        .ok_or(DecodeError::MissingSuffix)?;
+    Config::new(alphabet)
+        .decode(payload)
+        .map_err(|err| err.map_index(|index| index + prefix.len()))
+}
+
+/// Decodes several unpadded fields stored back to back, where field `i` is `lengths[i]` symbols
+/// long, e.g. `"MZXW6MFRGG"` with lengths `[5, 5]`.
+///
+/// Padding is never accepted, even if `alphabet` is padded. Each field must have a length an
+/// encoder can produce, otherwise [`DecodeError::InvalidLength`] is returned for it, and the
+/// lengths must add up to the length of `data`, otherwise the error is
+/// [`DecodeError::UnexpectedLength`]. Lengths whose sum does not fit in a `usize` can never
+/// match and are reported the same way, with `expected` set to `usize::MAX`. The index of an
+/// invalid character is relative to the start of `data`.
+///
+/// ```
+/// use base32::{decode_fields, Alphabet};
+///
+/// let fields = decode_fields(Alphabet::Rfc4648 { padding: false }, "MZXW6MFRGGMI", &[5, 5, 2]);
+/// assert_eq!(fields.unwrap(), [&b"foo"[..], b"abc", b"b"]);
+/// ```
+pub fn decode_fields(
+    alphabet: Alphabet,
+    data: &str,
+    lengths: &[usize],
+) -> Result<Vec<Vec<u8>>, DecodeError> {
+    let data = data.as_bytes();
+    let expected = lengths
+        .iter()
+        .try_fold(0usize, |sum, &length| sum.checked_add(length))
+        .unwrap_or(usize::MAX);
+    if data.len() != expected {
+        return Err(DecodeError::UnexpectedLength {
+            got: data.len(),
+            expected,
+        });
+    }
+    let config = Config::new(alphabet).padding(false);
+    let mut ret = Vec::with_capacity(lengths.len());
+    let mut offset = 0;
+    for &length in lengths {
+        if let 1 | 3 | 6 = length % 8 {
+            return Err(DecodeError::InvalidLength { length });
+        }
+        let field = &data[offset..offset + length];
+        let decoded = config
+            .decode_bytes(field)
+            .map_err(|err| err.map_index(|index| offset + index))?;
+        ret.push(decoded.data);
+        offset += length;
+    }
+    Ok(ret)
+}
+
+/// Returns the number of characters [`encode`] produces for `len` bytes of input. See
+/// [`Config::encoded_len`].
+pub fn encoded_len(alphabet: Alphabet, len: usize) -> usize {
+    Config::new(alphabet).encoded_len(len)
+}
+
+/// Returns the largest number of bytes whose [`encode`] output fits in `char_len` characters.
+/// See [`Config::max_input_bytes`].
+pub fn max_input_bytes(alphabet: Alphabet, char_len: usize) -> usize {
+    Config::new(alphabet).max_input_bytes(char_len)
+}
+
+/// An iterator over the running encoded length of a byte stream, created by
+/// [`encoded_char_count`].
+#[derive(Clone)]
+pub struct EncodedCharCount<I> {
+    bytes: I,
+    config: Config,
+    count: usize,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for EncodedCharCount<I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.bytes.next()?;
+        self.count += 1;
+        Some(self.config.encoded_len(self.count))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bytes.size_hint()
+    }
+}
+
+/// Wraps a byte iterator to yield, after each byte, the number of characters encoding the
+/// bytes so far would produce. Nothing is encoded or allocated, so a streaming encoder can use
+/// it to size its buffers as input arrives. With padding the count grows in steps of
+/// [`OUTPUT_CHUNK`] at the start of each group of [`INPUT_CHUNK`] bytes.
+///
+/// ```
+/// use base32::{encoded_char_count, Alphabet};
+///
+/// let counts = encoded_char_count(Alphabet::Crockford, b"foobar".iter().copied());
+/// assert_eq!(counts.collect::<Vec<_>>(), [2, 4, 5, 7, 8, 10]);
+///
+/// let counts = encoded_char_count(Alphabet::Rfc4648 { padding: true }, b"foobar".iter().copied());
+/// assert_eq!(counts.collect::<Vec<_>>(), [8, 8, 8, 8, 8, 16]);
+/// ```
+pub fn encoded_char_count<I: IntoIterator<Item = u8>>(
+    alphabet: Alphabet,
+    bytes: I,
+) -> EncodedCharCount<I::IntoIter> {
+    EncodedCharCount {
+        bytes: bytes.into_iter(),
+        config: Config::new(alphabet),
+        count: 0,
+    }
+}
+
+/// Checks every string in `items` and returns one result per item, in the same order, so that
+/// all the invalid fields of an imported file can be reported at once instead of stopping at
+/// the first. An item is valid if [`decode`] accepts it.
+///
+/// With the `parallel` feature, `validate_batch_par` spreads large batches over threads.
+///
+/// ```
+/// use base32::{validate_batch, Alphabet, DecodeError};
+///
+/// let results = validate_batch(Alphabet::Crockford, &["CSQPY", "CSQPU", ""]);
+/// assert_eq!(
+///     results,
+///     [Ok(()), Err(DecodeError::InvalidChar { index: 4, byte: b'U' }), Ok(())]
+/// );
+/// ```
+pub fn validate_batch(alphabet: Alphabet, items: &[&str]) -> Vec<Result<(), DecodeError>> {
+    let config = Config::new(alphabet);
+    items.iter().map(|item| config.decode(item).map(drop)).collect()
+}
+
+/// Decodes `data` with the first built-in alphabet that accepts it, for input known to be
+/// base32 of an unknown flavor. Returns the alphabet along with the bytes.
+///
+/// Many strings are valid in several alphabets, with different meanings, so the alphabets are
+/// tried in a fixed order, most widely used first:
+///
+/// 1. [`Alphabet::Rfc4648`], padded, then unpadded
+/// 2. [`Alphabet::Rfc4648Lower`], padded, then unpadded
+/// 3. [`Alphabet::Rfc4648Hex`], padded, then unpadded (with the `hex` feature)
+/// 4. [`Alphabet::Rfc4648HexLower`], padded, then unpadded (with the `hex` feature)
+/// 5. [`Alphabet::Crockford`]
+/// 6. [`Alphabet::Z`] (with the `zbase32` feature)
+/// 7. [`Alphabet::Bech32`] (with the `bech32` feature)
+///
+/// A padded alphabet only matches if the padding is exactly right, so the `padding` of the
+/// returned alphabet says whether the input was padded. Decoding is case-sensitive except for
+/// Crockford's. Use [`confidence`] to rank the alphabets instead of taking the first.
+///
+/// Returns [`DecodeError::NoMatchingAlphabet`] if no alphabet decodes `data`.
+///
+/// ```
+/// use base32::{decode_any, Alphabet};
+///
+/// let (alphabet, data) = decode_any("MZXW6===").unwrap();
+/// assert!(matches!(alphabet, Alphabet::Rfc4648 { padding: true }));
+/// assert_eq!(data, b"foo");
+///
+/// let (alphabet, _) = decode_any("mzxw6").unwrap();
+/// assert!(matches!(alphabet, Alphabet::Rfc4648Lower { padding: false }));
+/// ```
+pub fn decode_any(data: &str) -> Result<(Alphabet, Vec<u8>), DecodeError> {
+    let candidates = [
+        Alphabet::Rfc4648 { padding: true },
+        Alphabet::Rfc4648 { padding: false },
+        Alphabet::Rfc4648Lower { padding: true },
+        Alphabet::Rfc4648Lower { padding: false },
+        #[cfg(feature = "hex")]
+        Alphabet::Rfc4648Hex { padding: true },
+        #[cfg(feature = "hex")]
+        Alphabet::Rfc4648Hex { padding: false },
+        #[cfg(feature = "hex")]
+        Alphabet::Rfc4648HexLower { padding: true },
+        #[cfg(feature = "hex")]
+        Alphabet::Rfc4648HexLower { padding: false },
+        Alphabet::Crockford,
+        #[cfg(feature = "zbase32")]
+        Alphabet::Z,
+        #[cfg(feature = "bech32")]
+        Alphabet::Bech32,
+    ];
+    // Every candidate rejects non-ASCII input, and one that lacks any of the characters used
+    // (other than padding) can be skipped without attempting to decode.
+    if !data.is_ascii() {
+        return Err(DecodeError::NoMatchingAlphabet);
+    }
+    let used = data.bytes().fold(0u128, |used, c| used | 1 << c) & !(1 << b'=');
+    candidates
+        .iter()
+        .filter(|&&alphabet| used & !symbol_class(alphabet) == 0)
+        .find_map(|&alphabet| {
+            let config = Config::new(alphabet).strict_padding(true);
+            config.decode(data).ok().map(|decoded| (alphabet, decoded))
+        })
+        .ok_or(DecodeError::NoMatchingAlphabet)
+}
+
+/// Returns a rough score from 0.0 to 1.0 for how plausible it is that `data` was encoded with
+/// `alphabet`, for guessing the encoding of unlabelled input.
+///
+/// The score is the product of three factors:
+///
+/// * the fraction of characters, excluding trailing `=` on padded alphabets, that are symbols
+///   of the alphabet (0.0 for empty input);
+/// * 1.0 if that character count is a length an encoder can produce, i.e. 0, 2, 4, 5 or 7
+///   modulo 8, otherwise 0.5;
+/// * 1.0 if the padding is canonical for the alphabet, 0.75 if a padded alphabet is missing its
+///   padding, and 0.5 if the padding is present but the wrong amount.
+///
+/// ```
+/// use base32::{confidence, Alphabet};
+///
+/// assert_eq!(confidence(Alphabet::Rfc4648 { padding: true }, "MZXW6==="), 1.0);
+/// assert_eq!(confidence(Alphabet::Rfc4648 { padding: true }, "MZXW6"), 0.75);
+/// assert!(confidence(Alphabet::Rfc4648 { padding: true }, "mzxw6===") < 0.5);
+/// ```
+pub fn confidence(alphabet: Alphabet, data: &str) -> f32 {
+    let config = Config::new(alphabet);
+    let (body, padding) = config.strip_padding(data.as_bytes());
+    if body.is_empty() {
+        return 0.0;
+    }
+
+    let valid = body.iter().filter(|&&c| config.value(c).is_some()).count();
+    let valid_fraction = valid as f32 / body.len() as f32;
+
+    let length_factor = match body.len() % 8 {
+        0 | 2 | 4 | 5 | 7 => 1.0,
+        _ => 0.5,
+    };
+
+    let expected_padding = if config.padding {
+        (8 - body.len() % 8) % 8
+    } else {
+        0
+    };
+    let padding_factor = if padding == expected_padding {
+        1.0
+    } else if padding == 0 {
+        0.75
+    } else {
+        0.5
+    };
+
+    valid_fraction * length_factor * padding_factor
+}
+
+// Tests that involve an optional alphabet skip it when its feature is disabled, which leaves
+// the base32hex column of `RFC4648_VECTORS` unused without `hex`.
+#[cfg(test)]
+#[allow(dead_code, unused_attributes)]
+#[cfg_attr(not(feature = "hex"), allow(unused_variables))]
+mod test {
+    #[cfg(feature = "bech32")]
+    use super::Alphabet::Bech32;
+    use super::Alphabet::{Crockford, Custom, Rfc4648, Rfc4648Lower};
+    #[cfg(feature = "hex")]
+    use super::Alphabet::{Rfc4648Hex, Rfc4648HexLower};
+    #[cfg(feature = "zbase32")]
+    use super::Alphabet::Z;
+    use super::{
+        alphabet_diff, alphabet_regex, chunks_decodable, confidence, decode,
+        decode_and_canonicalize, decode_any, decode_block_aligned, decode_branchless, decode_chars,
+        decode_dns_labels, decode_exact, decode_fields, decode_friendly, decode_hash32,
+        decode_ignoring, decode_license_key, decode_rfc4648_auto, decode_trimmed,
+        decode_unpadded_exact, decode_with_fallback, decode_with_spans, decoded_len, encode,
+        encode_array, encode_block_aligned, encode_cased, encode_chunks, encode_dns_labels,
+        encode_hash32, encode_line, encoded_char_count, encoded_len, is_valid_char, matches_bytes,
+        matches_bytes_constant_time, max_input_bytes, normalize, pack_bits, regroup_crockford,
+        spread_bits, symbol, unpadded_len, validate_batch, value, Base32Error, BitOrder, Case,
+        Config, CustomAlphabet, DecodeError,
+    };
+    use alloc::borrow::Cow;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::{Debug, Error, Formatter};
+    use quickcheck::{Arbitrary, Gen};
+
+    #[derive(Clone)]
+    struct B32 {
+        c: u8,
+    }
+
+    impl Arbitrary for B32 {
+        fn arbitrary(g: &mut Gen) -> B32 {
+            B32 {
+                c: *g.choose(b"0123456789ABCDEFGHJKMNPQRSTVWXYZ").unwrap(),
+            }
+        }
+    }
+
+    impl Debug for B32 {
+        fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+            (self.c as char).fmt(f)
+        }
+    }
+
+    #[test]
+    fn masks_crockford() {
+        assert_eq!(
+            encode(Crockford, &[0xF8, 0x3E, 0x0F, 0x83, 0xE0]),
+            "Z0Z0Z0Z0"
+        );
+        assert_eq!(
+            encode(Crockford, &[0x07, 0xC1, 0xF0, 0x7C, 0x1F]),
+            "0Z0Z0Z0Z"
+        );
+        assert_eq!(
+            decode(Crockford, "Z0Z0Z0Z0").unwrap(),
+            [0xF8, 0x3E, 0x0F, 0x83, 0xE0]
+        );
+        assert_eq!(
+            decode(Crockford, "0Z0Z0Z0Z").unwrap(),
+            [0x07, 0xC1, 0xF0, 0x7C, 0x1F]
+        );
+    }
+
+    #[test]
+    fn masks_rfc4648() {
+        assert_eq!(
+            encode(Rfc4648 { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
+            "7A7H7A7H"
+        );
+        assert_eq!(
+            encode(Rfc4648 { padding: false }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
+            "O7A7O7A7"
+        );
+        assert_eq!(
+            decode(Rfc4648 { padding: false }, "7A7H7A7H").unwrap(),
+            [0xF8, 0x3E, 0x7F, 0x83, 0xE7]
+        );
+        assert_eq!(
+            decode(Rfc4648 { padding: false }, "O7A7O7A7").unwrap(),
+            [0x77, 0xC1, 0xF7, 0x7C, 0x1F]
+        );
+        assert_eq!(
+            encode(Rfc4648 { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83]),
+            "7A7H7AY"
+        );
+    }
+
+    #[test]
+    fn masks_rfc4648_pad() {
+        assert_eq!(
+            encode(Rfc4648 { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
+            "7A7H7A7H"
+        );
+        assert_eq!(
+            encode(Rfc4648 { padding: true }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
+            "O7A7O7A7"
+        );
+        assert_eq!(
+            decode(Rfc4648 { padding: true }, "7A7H7A7H").unwrap(),
+            [0xF8, 0x3E, 0x7F, 0x83, 0xE7]
+        );
+        assert_eq!(
+            decode(Rfc4648 { padding: true }, "O7A7O7A7").unwrap(),
+            [0x77, 0xC1, 0xF7, 0x7C, 0x1F]
+        );
+        assert_eq!(
+            encode(Rfc4648 { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83]),
+            "7A7H7AY="
+        );
+    }
+
+    #[test]
+    fn masks_rfc4648_lower() {
+        assert_eq!(
+            encode(Rfc4648Lower { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
+            "7a7h7a7h"
+        );
+        assert_eq!(
+            encode(Rfc4648Lower { padding: false }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
+            "o7a7o7a7"
+        );
+        assert_eq!(
+            decode(Rfc4648Lower { padding: false }, "7a7h7a7h").unwrap(),
+            [0xF8, 0x3E, 0x7F, 0x83, 0xE7]
+        );
+        assert_eq!(
+            decode(Rfc4648Lower { padding: false }, "o7a7o7a7").unwrap(),
+            [0x77, 0xC1, 0xF7, 0x7C, 0x1F]
+        );
+        assert_eq!(
+            encode(Rfc4648Lower { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83]),
+            "7a7h7ay"
+        );
+    }
+
+    #[test]
+    fn masks_rfc4648_lower_pad() {
+        assert_eq!(
+            encode(Rfc4648Lower { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
+            "7a7h7a7h"
+        );
+        assert_eq!(
+            encode(Rfc4648Lower { padding: true }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
+            "o7a7o7a7"
+        );
+        assert_eq!(
+            decode(Rfc4648Lower { padding: true }, "7a7h7a7h").unwrap(),
+            [0xF8, 0x3E, 0x7F, 0x83, 0xE7]
+        );
+        assert_eq!(
+            decode(Rfc4648Lower { padding: true }, "o7a7o7a7").unwrap(),
+            [0x77, 0xC1, 0xF7, 0x7C, 0x1F]
+        );
+        assert_eq!(
+            encode(Rfc4648Lower { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83]),
+            "7a7h7ay="
+        );
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn masks_rfc4648_hex() {
+        assert_eq!(
+            encode(Rfc4648Hex { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
+            "V0V7V0V7"
+        );
+        assert_eq!(
+            encode(Rfc4648Hex { padding: false }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
+            "EV0VEV0V"
+        );
+        assert_eq!(
+            decode(Rfc4648Hex { padding: false }, "7A7H7A7H").unwrap(),
+            [0x3A, 0x8F, 0x13, 0xA8, 0xF1]
+        );
+        assert_eq!(
+            decode(Rfc4648Hex { padding: false }, "O7A7O7A7").unwrap(),
+            [0xC1, 0xD4, 0x7C, 0x1D, 0x47]
+        );
+        assert_eq!(
+            encode(Rfc4648Hex { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83]),
+            "V0V7V0O"
+        );
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn masks_rfc4648_hex_pad() {
+        assert_eq!(
+            encode(Rfc4648Hex { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
+            "V0V7V0V7"
+        );
+        assert_eq!(
+            encode(Rfc4648Hex { padding: true }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
+            "EV0VEV0V"
+        );
+        assert_eq!(
+            decode(Rfc4648Hex { padding: true }, "7A7H7A7H").unwrap(),
+            [0x3A, 0x8F, 0x13, 0xA8, 0xF1]
+        );
+        assert_eq!(
+            decode(Rfc4648Hex { padding: true }, "O7A7O7A7").unwrap(),
+            [0xC1, 0xD4, 0x7C, 0x1D, 0x47]
+        );
+        assert_eq!(
+            encode(Rfc4648Hex { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83]),
+            "V0V7V0O="
+        );
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn masks_rfc4648_hex_lower() {
+        assert_eq!(
+            encode(Rfc4648HexLower { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
+            "v0v7v0v7"
+        );
+        assert_eq!(
+            encode(Rfc4648HexLower { padding: false }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
+            "ev0vev0v"
+        );
+        assert_eq!(
+            decode(Rfc4648HexLower { padding: false }, "7a7h7a7h").unwrap(),
+            [0x3A, 0x8F, 0x13, 0xA8, 0xF1]
+        );
+        assert_eq!(
+            decode(Rfc4648HexLower { padding: false }, "o7a7o7a7").unwrap(),
+            [0xC1, 0xD4, 0x7C, 0x1D, 0x47]
+        );
+        assert_eq!(
+            encode(Rfc4648HexLower { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83]),
+            "v0v7v0o"
+        );
+    }
+
+    #[cfg(feature = "hex")]
+    #[test]
+    fn masks_rfc4648_hex_lower_pad() {
+        assert_eq!(
+            encode(Rfc4648HexLower { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
+            "v0v7v0v7"
+        );
+        assert_eq!(
+            encode(Rfc4648HexLower { padding: true }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
+            "ev0vev0v"
+        );
+        assert_eq!(
+            decode(Rfc4648HexLower { padding: true }, "7a7h7a7h").unwrap(),
+            [0x3A, 0x8F, 0x13, 0xA8, 0xF1]
+        );
+        assert_eq!(
+            decode(Rfc4648HexLower { padding: true }, "o7a7o7a7").unwrap(),
+            [0xC1, 0xD4, 0x7C, 0x1D, 0x47]
+        );
+        assert_eq!(
+            encode(Rfc4648HexLower { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83]),
+            "v0v7v0o="
+        );
+    }
+
+    #[cfg(feature = "zbase32")]
+    #[test]
+    fn masks_z() {
+        assert_eq!(
+            encode(Z, &[0xF8, 0x3E, 0x0F, 0x83, 0xE0]),
+            "9y9y9y9y"
+        );
+        assert_eq!(
+            encode(Z, &[0x07, 0xC1, 0xF0, 0x7C, 0x1F]),
+            "y9y9y9y9"
+        );
+        assert_eq!(
+            decode(Z, "9y9y9y9y").unwrap(),
+            [0xF8, 0x3E, 0x0F, 0x83, 0xE0]
+        );
+        assert_eq!(
+            decode(Z, "y9y9y9y9").unwrap(),
+            [0x07, 0xC1, 0xF0, 0x7C, 0x1F]
+        );
+    }
+
+    #[test]
+    fn padding() {
+        let num_padding = [0, 6, 4, 3, 1];
+        for i in 1..6 {
+            let encoded = encode(
+                Rfc4648 { padding: true },
+                (0..(i as u8)).collect::<Vec<u8>>().as_ref(),
+            );
+            assert_eq!(encoded.len(), 8);
+            for j in 0..(num_padding[i % 5]) {
+                assert_eq!(encoded.as_bytes()[encoded.len() - j - 1], b'=');
+            }
+            for j in 0..(8 - num_padding[i % 5]) {
+                assert!(encoded.as_bytes()[j] != b'=');
+            }
+        }
+    }
+
+    #[test]
+    fn partial_chunks() {
+        const DATA: [u8; 9] = [0xA5, 0x99, 0xDD, 0x11, 0x55, 0x89, 0xCD, 0x01, 0x45];
+        // Input length, padded and unpadded encoding of that prefix of `DATA`.
+        let vectors = [
+            (1, "UU======", "UU"),
+            (2, "UWMQ====", "UWMQ"),
+            (3, "UWM52===", "UWM52"),
+            (4, "UWM52EI=", "UWM52EI"),
+            (6, "UWM52EKVRE======", "UWM52EKVRE"),
+            (7, "UWM52EKVRHGQ====", "UWM52EKVRHGQ"),
+            (8, "UWM52EKVRHGQC===", "UWM52EKVRHGQC"),
+            (9, "UWM52EKVRHGQCRI=", "UWM52EKVRHGQCRI"),
+        ];
+        for &(len, padded, unpadded) in &vectors {
+            let data = &DATA[..len];
+            assert_eq!(encode(Rfc4648 { padding: true }, data), padded);
+            assert_eq!(encode(Rfc4648 { padding: false }, data), unpadded);
+            assert_eq!(decode(Rfc4648 { padding: true }, padded).unwrap(), data);
+            assert_eq!(decode(Rfc4648 { padding: false }, unpadded).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn every_length() {
+        let data = (0..=255).collect::<Vec<u8>>();
+        let alphabets = [
+            Crockford,
+            Rfc4648 { padding: true },
+            Rfc4648 { padding: false },
+            #[cfg(feature = "zbase32")]
+            Z,
+        ];
+        for &alphabet in &alphabets {
+            for len in 0..41 {
+                let encoded = encode(alphabet, &data[..len]);
+                assert_eq!(decode(alphabet, &encoded).unwrap(), &data[..len]);
+            }
+        }
+
+        let symbols = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567ABCDEFGH";
+        for len in 0..41 {
+            for padding in 0..8 {
+                let mut data = String::from(&symbols[..len]);
+                data.extend(core::iter::repeat_n('=', padding));
+                for &alphabet in &alphabets {
+                    let _ = Config::new(alphabet).decode(&data);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn config_padding() {
+        assert_eq!(
+            Config::new(Crockford).padding(true).encode(&[0xF8, 0x3E]),
+            "Z0Z0===="
+        );
+        #[cfg(feature = "zbase32")]
+        assert_eq!(
+            Config::new(Z).padding(true).encode(&[0xF8, 0x3E]),
+            "9y9y===="
+        );
+        assert_eq!(
+            Config::new(Rfc4648 { padding: true })
+                .padding(false)
+                .encode(&[0xF8, 0x3E]),
+            encode(Rfc4648 { padding: false }, &[0xF8, 0x3E])
+        );
+        #[cfg(feature = "hex")]
+        assert_eq!(
+            Config::new(Rfc4648Hex { padding: false })
+                .padding(true)
+                .encode(&[0xF8, 0x3E]),
+            encode(Rfc4648Hex { padding: true }, &[0xF8, 0x3E])
+        );
+        assert_eq!(
+            Config::new(Crockford)
+                .padding(true)
+                .decode("Z0Z0====")
+                .unwrap(),
+            [0xF8, 0x3E]
+        );
+        assert_eq!(
+            Config::new(Crockford).decode("Z0Z0===="),
+            Err(DecodeError::InvalidChar {
+                index: 4,
+                byte: b'='
+            })
+        );
+    }
+
+    #[test]
+    fn percent_encoded_padding() {
+        let config = Config::new(Rfc4648 { padding: true }).percent_encoded_padding(true);
+        assert_eq!(config.decode("MZXW6%3D%3D%3D").unwrap(), b"foo");
+        assert_eq!(config.decode("MZXW6%3d%3d%3d").unwrap(), b"foo");
+        assert_eq!(config.decode("MZXW6=%3d%3D").unwrap(), b"foo");
+        assert_eq!(config.decode("MZXW6===").unwrap(), b"foo");
+        assert_eq!(
+            config.decode("MZ%3DXW6==="),
+            Err(DecodeError::InvalidChar {
+                index: 2,
+                byte: b'%'
+            })
+        );
+        assert_eq!(
+            Config::new(Rfc4648 { padding: true }).decode("MZXW6%3D%3D%3D"),
+            Err(DecodeError::InvalidChar {
+                index: 5,
+                byte: b'%'
+            })
+        );
+        assert_eq!(
+            Config::new(Rfc4648 { padding: false })
+                .percent_encoded_padding(true)
+                .decode("MZXW6%3D"),
+            Err(DecodeError::InvalidChar {
+                index: 5,
+                byte: b'%'
+            })
+        );
+    }
+
+    const RFC4648_VECTORS: &[(&[u8], &str, &str)] = &[
+        (b"", "", ""),
+        (b"f", "MY======", "CO======"),
+        (b"fo", "MZXQ====", "CPNG===="),
+        (b"foo", "MZXW6===", "CPNMU==="),
+        (b"foob", "MZXW6YQ=", "CPNMUOG="),
+        (b"fooba", "MZXW6YTB", "CPNMUOJ1"),
+        (b"foobar", "MZXW6YTBOI======", "CPNMUOJ1E8======"),
+    ];
+
+    #[test]
+    fn rfc4648_vectors() {
+        for &(data, base32, base32hex) in RFC4648_VECTORS {
+            assert_eq!(encode(Rfc4648 { padding: true }, data), base32);
+            assert_eq!(decode(Rfc4648 { padding: true }, base32).unwrap(), data);
+            #[cfg(feature = "hex")]
+            {
+                assert_eq!(encode(Rfc4648Hex { padding: true }, data), base32hex);
+                assert_eq!(
+                    decode(Rfc4648Hex { padding: true }, base32hex).unwrap(),
+                    data
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rfc4648_auto_padding() {
+        for &(data, base32, _) in RFC4648_VECTORS {
+            assert_eq!(decode_rfc4648_auto(base32).unwrap(), data);
+            assert_eq!(
+                decode_rfc4648_auto(base32.trim_end_matches('=')).unwrap(),
+                data
+            );
+        }
+        assert_eq!(
+            decode_rfc4648_auto("MZXW6Y"),
+            Err(DecodeError::InvalidLength { length: 6 })
+        );
+        assert_eq!(
+            decode_rfc4648_auto("MZ=XW6Y"),
+            Err(DecodeError::InvalidChar {
+                index: 2,
+                byte: b'='
+            })
+        );
+    }
+
+    #[test]
+    fn friendly() {
+        for &(data, base32, base32hex) in RFC4648_VECTORS {
+            let grouped = |s: &str| {
+                let s = s.trim_end_matches('=').to_ascii_lowercase();
+                let groups = s.as_bytes().chunks(4).map(|group| core::str::from_utf8(group));
+                groups.map(Result::unwrap).collect::<Vec<_>>().join("- ")
+            };
+            assert_eq!(decode_friendly(Rfc4648 { padding: true }, base32).unwrap(), data);
+            assert_eq!(decode_friendly(Rfc4648 { padding: false }, base32).unwrap(), data);
+            assert_eq!(
+                decode_friendly(Rfc4648 { padding: true }, &grouped(base32)).unwrap(),
+                data
+            );
+            #[cfg(feature = "hex")]
+            assert_eq!(
+                decode_friendly(Rfc4648Hex { padding: false }, &grouped(base32hex)).unwrap(),
+                data
+            );
+        }
+        assert_eq!(decode_friendly(Crockford, "\tcsQp-yRkI-e8\r\n").unwrap(), b"foobar");
+        assert_eq!(
+            decode_friendly(Crockford, "C-S-Q-P-Y-O-L").ok(),
+            decode(Crockford, "CSQPY01")
+        );
+        assert_eq!(
+            decode_friendly(Crockford, "CSQP-YU"),
+            Err(DecodeError::InvalidChar {
+                index: 6,
+                byte: b'U'
+            })
+        );
+        assert_eq!(
+            decode_friendly(Rfc4648 { padding: false }, "MZX-W6-Y"),
+            Err(DecodeError::InvalidLength { length: 6 })
+        );
+
+        // Only a trailing run of `=` is padding, and only in alphabets that pad.
+        assert_eq!(
+            decode_friendly(Crockford, "CS=QPYRK"),
+            Err(DecodeError::InvalidChar {
+                index: 2,
+                byte: b'='
+            })
+        );
+        assert_eq!(
+            decode_friendly(Crockford, "CSQPY==="),
+            Err(DecodeError::InvalidChar {
+                index: 5,
+                byte: b'='
+            })
+        );
+        for &padding in &[true, false] {
+            assert_eq!(
+                decode_friendly(Rfc4648 { padding }, "MZ=W6==="),
+                Err(DecodeError::InvalidChar {
+                    index: 2,
+                    byte: b'='
+                })
+            );
+            assert_eq!(
+                decode_friendly(Rfc4648 { padding }, "MZ=W6"),
+                Err(DecodeError::InvalidChar {
+                    index: 2,
+                    byte: b'='
+                })
+            );
+            assert_eq!(decode_friendly(Rfc4648 { padding }, "mzxw-6= ==\n").unwrap(), b"foo");
+        }
+
+        // A symbol is never skipped.
+        static DASH: CustomAlphabet = base32_alphabet!("-123456789ABCDEFGHIJKLMNOPQRSTUV");
+        assert_eq!(decode_friendly(Custom(&DASH), "--").unwrap(), [0]);
+    }
+
+    #[test]
+    fn rfc4648_vectors_transformed() {
+        fn alternate_case(s: &str) -> String {
+            s.chars()
+                .enumerate()
+                .map(|(i, c)| {
+                    if i % 2 == 0 {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c
+                    }
+                })
+                .collect()
+        }
+        fn spaced(s: &str) -> String {
+            s.chars().flat_map(|c| [c, ' ']).collect()
+        }
+        fn wrapped(s: &str) -> String {
+            let mut ret = String::from("\t");
+            for (i, c) in s.chars().enumerate() {
+                if i > 0 && i % 4 == 0 {
+                    ret.push_str("\r\n");
+                }
+                ret.push(c);
+            }
+            ret.push('\n');
+            ret
+        }
+        let transforms: &[fn(&str) -> String] = &[
+            |s| s.into(),
+            |s| s.to_ascii_lowercase(),
+            alternate_case,
+            spaced,
+            wrapped,
+            |s| spaced(&s.to_ascii_lowercase()),
+            |s| wrapped(&alternate_case(s)),
+        ];
+        for &(data, base32, base32hex) in RFC4648_VECTORS {
+            for &(alphabet, encoded) in &[
+                (Rfc4648 { padding: true }, base32),
+                #[cfg(feature = "hex")]
+                (Rfc4648Hex { padding: true }, base32hex),
+            ] {
+                let unpadded = encoded.trim_end_matches('=');
+                let padded = Config::new(alphabet)
+                    .case_insensitive(true)
+                    .ignore_whitespace(true);
+                let unpadded_config = padded.padding(false);
+                for transform in transforms {
+                    assert_eq!(padded.decode(&transform(encoded)).unwrap(), data);
+                    assert_eq!(padded.decode(&transform(unpadded)).unwrap(), data);
+                    assert_eq!(unpadded_config.decode(&transform(unpadded)).unwrap(), data);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn case_and_whitespace_are_strict_by_default() {
+        let config = Config::new(Rfc4648 { padding: true });
+        assert_eq!(
+            config.decode("mzxw6==="),
+            Err(DecodeError::InvalidChar {
+                index: 0,
+                byte: b'm'
+            })
+        );
+        assert_eq!(
+            config.decode("MZXW 6==="),
+            Err(DecodeError::InvalidChar {
+                index: 4,
+                byte: b' '
+            })
+        );
+        assert_eq!(
+            config.ignore_whitespace(true).decode("MZ XW\n6,=="),
+            Err(DecodeError::InvalidChar {
+                index: 7,
+                byte: b','
+            })
+        );
+    }
+
+    #[test]
+    fn decode_detailed_padding() {
+        let config = Config::new(Rfc4648 { padding: true }).percent_encoded_padding(true);
+        for &(data, base32, _) in RFC4648_VECTORS {
+            let decoded = config.decode_detailed(base32).unwrap();
+            assert_eq!(decoded.data, data);
+            assert_eq!(decoded.expected_padding, decoded.actual_padding);
+
+            let unpadded = base32.trim_end_matches('=');
+            let decoded = config.decode_detailed(unpadded).unwrap();
+            assert_eq!(decoded.data, data);
+            assert_eq!(decoded.expected_padding, base32.len() - unpadded.len());
+            assert_eq!(decoded.actual_padding, 0);
+        }
+        let decoded = config.decode_detailed("MZXW6%3D=").unwrap();
+        assert_eq!((decoded.expected_padding, decoded.actual_padding), (3, 2));
+
+        let decoded = Config::new(Rfc4648 { padding: false })
+            .decode_detailed("MZXW6")
+            .unwrap();
+        assert_eq!((decoded.expected_padding, decoded.actual_padding), (0, 0));
+    }
+
+    #[test]
+    fn strict_padding() {
+        let lenient = Config::new(Rfc4648 { padding: true });
+        let strict = lenient.strict_padding(true);
+        for &(data, base32, _) in RFC4648_VECTORS {
+            let unpadded = base32.trim_end_matches('=');
+            assert_eq!(lenient.decode(base32).unwrap(), data);
+            assert_eq!(lenient.decode(unpadded).unwrap(), data);
+            assert_eq!(strict.decode(base32).unwrap(), data);
+            if unpadded != base32 {
+                assert_eq!(
+                    strict.decode(unpadded),
+                    Err(DecodeError::InvalidPadding {
+                        expected: base32.len() - unpadded.len(),
+                        actual: 0
+                    })
+                );
+            }
+        }
+        assert_eq!(
+            strict.decode("MZXW6="),
+            Err(DecodeError::InvalidPadding {
+                expected: 3,
+                actual: 1
+            })
+        );
+        let strict = Config::new(Crockford).strict_padding(true);
+        assert_eq!(strict.decode("CSQPYRK1E8").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn invalid_digit() {
+        use alloc::string::ToString;
+
+        for &digit in b"0189" {
+            let mut data = String::from("MZXW6===");
+            data.insert(2, digit as char);
+            data.pop();
+            for &alphabet in &[Rfc4648 { padding: true }, Rfc4648Lower { padding: true }] {
+                let data = match alphabet {
+                    Rfc4648Lower { .. } => data.to_ascii_lowercase(),
+                    _ => data.clone(),
+                };
+                assert_eq!(
+                    Config::new(alphabet).decode(&data),
+                    Err(DecodeError::InvalidDigit {
+                        index: 2,
+                        byte: digit
+                    })
+                );
+                assert_eq!(
+                    decode_chars(alphabet, data.chars()),
+                    Err(DecodeError::InvalidDigit {
+                        index: 2,
+                        byte: digit
+                    })
+                );
+            }
+        }
+        #[cfg(feature = "zbase32")]
+        assert_eq!(
+            Config::new(Z).decode("yy0y"),
+            Err(DecodeError::InvalidChar {
+                index: 2,
+                byte: b'0'
+            })
+        );
+        let err = Config::new(Rfc4648 { padding: false })
+            .decode("MZ0A")
+            .unwrap_err();
+        assert!(err.to_string().contains("did you mean Crockford"));
+        assert_eq!(
+            Config::new(Rfc4648 { padding: false }).decode("MZ!A"),
+            Err(DecodeError::InvalidChar {
+                index: 2,
+                byte: b'!'
+            })
+        );
+    }
+
+    #[test]
+    fn confidence_scores() {
+        for &(_, base32, base32hex) in &RFC4648_VECTORS[1..] {
+            assert_eq!(confidence(Rfc4648 { padding: true }, base32), 1.0);
+            #[cfg(feature = "hex")]
+            assert_eq!(confidence(Rfc4648Hex { padding: true }, base32hex), 1.0);
+        }
+        assert_eq!(confidence(Rfc4648 { padding: true }, ""), 0.0);
+        assert_eq!(confidence(Rfc4648 { padding: false }, "MZXW6"), 1.0);
+        assert_eq!(confidence(Rfc4648 { padding: true }, "MZXW6="), 0.5);
+        assert_eq!(confidence(Rfc4648 { padding: true }, "mzxw6==="), 0.2);
+        assert_eq!(confidence(Rfc4648 { padding: false }, "MZXW6==="), 0.625);
+        assert_eq!(confidence(Crockford, "MZX"), 0.5);
+        assert_eq!(confidence(Crockford, "mzxw6"), 1.0);
+        #[cfg(feature = "zbase32")]
+        assert!(confidence(Z, "MZXW6YTB") < confidence(Rfc4648 { padding: true }, "MZXW6YTB"));
+    }
+
+    #[test]
+    fn bit_order() {
+        let msb = Config::new(Rfc4648 { padding: true });
+        let lsb = msb.bit_order(BitOrder::LsbFirst);
+        assert_eq!(msb.encode(&[0x01]), "AE======");
+        assert_eq!(lsb.encode(&[0x01]), "BA======");
+        assert_eq!(msb.encode(&[0x80]), "QA======");
+        assert_eq!(lsb.encode(&[0x80]), "AE======");
+        assert_eq!(msb.encode(b"fooba"), "MZXW6YTB");
+        assert_eq!(lsb.encode(b"fooba"), "G336GRFM");
+        assert_eq!(lsb.decode("G336GRFM").unwrap(), b"fooba");
+        assert_eq!(lsb.decode("BA======").unwrap(), [0x01]);
+    }
+
+    #[test]
+    fn invertible_lsb_first() {
+        fn test(data: Vec<u8>) -> bool {
+            let config = Config::new(Crockford).bit_order(BitOrder::LsbFirst);
+            config
+                .decode(config.encode(data.as_ref()).as_ref())
+                .unwrap()
+                == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool)
+    }
+
+    #[test]
+    fn decode_chars_matches_decode() {
+        fn test(data: Vec<u8>) -> bool {
+            let encoded = encode(Rfc4648 { padding: true }, &data);
+            decode_chars(Rfc4648 { padding: true }, encoded.chars()).unwrap() == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
 
-pub fn encode(alphabet: Alphabet, data: &[u8]) -> String {
-    let (alphabet, padding) = match alphabet {
-        Alphabet::Crockford => (CROCKFORD, false),
-        Alphabet::Rfc4648 { padding } => (RFC4648, padding),
-        Alphabet::Rfc4648Lower { padding } => (RFC4648_LOWER, padding),
-        Alphabet::Rfc4648Hex { padding } => (RFC4648_HEX, padding),
-        Alphabet::Rfc4648HexLower { padding } => (RFC4648_HEX_LOWER, padding),
-        Alphabet::Z => (Z, false),
-    };
-    let mut ret = Vec::with_capacity((data.len() + 3) / 4 * 5);
+        for &(data, base32, _) in RFC4648_VECTORS {
+            let unpadded = base32.trim_end_matches('=');
+            assert_eq!(
+                decode_chars(Rfc4648 { padding: true }, base32.chars()).unwrap(),
+                data
+            );
+            assert_eq!(
+                decode_chars(Rfc4648 { padding: false }, unpadded.chars()).unwrap(),
+                data
+            );
+        }
+    }
+
+    #[test]
+    fn control_characters() {
+        use alloc::string::ToString;
 
-    for chunk in data.chunks(5) {
-        let buf = {
-            let mut buf = [0u8; 5];
-            for (i, &b) in chunk.iter().enumerate() {
-                buf[i] = b;
+        let alphabets = [
+            Crockford,
+            Rfc4648 { padding: true },
+            Rfc4648Lower { padding: false },
+            #[cfg(feature = "zbase32")]
+            Z,
+        ];
+        for &alphabet in &alphabets {
+            for &byte in &[0x00, 0x1B, 0x0B, 0x7F] {
+                let zero = symbol(alphabet, 0).unwrap();
+                let input = [zero, zero, byte, zero];
+                let input = core::str::from_utf8(&input).unwrap();
+                let expected = DecodeError::InvalidChar { index: 2, byte };
+                let config = Config::new(alphabet);
+                // Case folding and whitespace skipping do not apply to control characters;
+                // vertical tab is not ASCII whitespace.
+                let lenient = config.case_insensitive(true).ignore_whitespace(true);
+                for config in &[config, lenient] {
+                    assert_eq!(config.decode(input), Err(expected));
+                    assert_eq!(config.decode_chars(input.chars()), Err(expected));
+                    assert_eq!(config.decode_partial(input).1, Some(expected));
+                    assert_eq!(config.decode_small::<8>(input).err(), Some(expected));
+                }
             }
-            buf
-        };
-        ret.push(alphabet[((buf[0] & 0xF8) >> 3) as usize]);
-        ret.push(alphabet[(((buf[0] & 0x07) << 2) | ((buf[1] & 0xC0) >> 6)) as usize]);
-        ret.push(alphabet[((buf[1] & 0x3E) >> 1) as usize]);
-        ret.push(alphabet[(((buf[1] & 0x01) << 4) | ((buf[2] & 0xF0) >> 4)) as usize]);
-        ret.push(alphabet[(((buf[2] & 0x0F) << 1) | (buf[3] >> 7)) as usize]);
-        ret.push(alphabet[((buf[3] & 0x7C) >> 2) as usize]);
-        ret.push(alphabet[(((buf[3] & 0x03) << 3) | ((buf[4] & 0xE0) >> 5)) as usize]);
-        ret.push(alphabet[(buf[4] & 0x1F) as usize]);
-    }
-
-    if data.len() % 5 != 0 {
-        let len = ret.len();
-        let num_extra = 8 - (data.len() % 5 * 8 + 4) / 5;
-        if padding {
-            for i in 1..num_extra + 1 {
-                ret[len - i] = b'=';
+            assert!(!is_valid_char(alphabet, '\0'));
+            assert_eq!(value(alphabet, 0x1B), None);
+        }
+        assert_eq!(
+            DecodeError::InvalidChar {
+                index: 3,
+                byte: 0x1B
             }
-        } else {
-            ret.truncate(len - num_extra);
-        }
-    }
-
-    String::from_utf8(ret).unwrap()
-}
-
-/*
-     0,  1,  2,  3,  4,  5,  6,  7,  8,  9,  :,  ;,  <,  =,  >,  ?,  @,  A,  B,  C,
-     D,  E,  F,  G,  H,  I,  J,  K,  L,  M,  N,  O,  P,  Q,  R,  S,  T,  U,  V,  W,
-     X,  Y,  Z,  [,  \,  ],  ^,  _,  `,  a,  b,  c,  d,  e,  f,  g,  h,  i,  j,  k,
-     l,  m,  n,  o,  p,  q,  r,  s,  t,  u,  v,  w,  x,  y,  z,
-*/
-
-const CROCKFORD_INV: [i8; 75] = [
-     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, -1, -1, -1, -1, -1, -1, -1, 10, 11, 12,
-    13, 14, 15, 16, 17,  1, 18, 19,  1, 20, 21,  0, 22, 23, 24, 25, 26, -1, 27, 28,
-    29, 30, 31, -1, -1, -1, -1, -1, -1, 10, 11, 12, 13, 14, 15, 16, 17,  1, 18, 19,
-     1, 20, 21,  0, 22, 23, 24, 25, 26, -1, 27, 28, 29, 30, 31,
-];
-const RFC4648_INV: [i8; 75] = [
-    -1, -1, 26, 27, 28, 29, 30, 31, -1, -1, -1, -1, -1, -1, -1, -1, -1,  0,  1,  2,
-     3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
-    23, 24, 25, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-];
-const RFC4648_INV_PAD: [i8; 75] = [
-    -1, -1, 26, 27, 28, 29, 30, 31, -1, -1, -1, -1, -1,  0, -1, -1, -1,  0,  1,  2,
-     3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22,
-    23, 24, 25, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-];
-const RFC4648_INV_LOWER: [i8; 75] = [
-    -1, -1, 26, 27, 28, 29, 30, 31, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1,  0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10,
-    11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
-];
-const RFC4648_INV_LOWER_PAD: [i8; 75] = [
-    -1, -1, 26, 27, 28, 29, 30, 31, -1, -1, -1, -1, -1,  0, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1,  0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10,
-    11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
-];
-const RFC4648_INV_HEX: [i8; 75] = [
-     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, -1, -1, -1, -1, -1, -1, -1, 10, 11, 12,
-    13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-];
-const RFC4648_INV_HEX_PAD: [i8; 75] = [
-     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, -1, -1, -1,  0, -1, -1, -1, 10, 11, 12,
-    13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-];
-const RFC4648_INV_HEX_LOWER: [i8; 75] = [
-     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
-    21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, -1, -1, -1, -1,
-];
-const RFC4648_INV_HEX_LOWER_PAD: [i8; 75] = [
-     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, -1, -1, -1,  0, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20,
-    21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, -1, -1, -1, -1,
-];
-const Z_INV: [i8; 75] = [
-    -1, 18, -1, 25, 26, 27, 30, 29,  7, 31, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
-    -1, -1, -1, -1, -1, -1, -1, -1, -1, 24,  1, 12,  3,  8,  5,  6, 28, 21,  9, 10,
-    -1, 11,  2, 16, 13, 14,  4, 22, 17, 19, -1, 20, 15,  0, 23,
-];
+            .to_string(),
+            "invalid character '\\u{1b}' at index 3"
+        );
+    }
 
-pub fn decode(alphabet: Alphabet, data: &str) -> Option<Vec<u8>> {
-    if !data.is_ascii() {
-        return None;
+    #[test]
+    fn decode_chars_invalid() {
+        assert_eq!(
+            decode_chars(Rfc4648 { padding: true }, "MZ=XW6==".chars()),
+            Err(DecodeError::InvalidChar {
+                index: 3,
+                byte: b'X'
+            })
+        );
+        assert_eq!(
+            decode_chars(Rfc4648 { padding: false }, "MZXW6===".chars()),
+            Err(DecodeError::InvalidChar {
+                index: 5,
+                byte: b'='
+            })
+        );
+        assert_eq!(
+            decode_chars(Crockford, "0é0".chars()),
+            Err(DecodeError::NonAscii { index: 1 })
+        );
     }
-    let data = data.as_bytes();
-    let alphabet = match alphabet {
-        Alphabet::Crockford => CROCKFORD_INV, // supports both upper and lower case
-        Alphabet::Rfc4648 { padding } => if padding { RFC4648_INV_PAD } else { RFC4648_INV }
-        Alphabet::Rfc4648Lower { padding } => if padding { RFC4648_INV_LOWER_PAD } else { RFC4648_INV_LOWER }
-        Alphabet::Rfc4648Hex { padding } => if padding { RFC4648_INV_HEX_PAD } else { RFC4648_INV_HEX }
-        Alphabet::Rfc4648HexLower { padding } => if padding { RFC4648_INV_HEX_LOWER_PAD } else { RFC4648_INV_HEX_LOWER }
-        Alphabet::Z => Z_INV,
-    };
-    let mut unpadded_data_length = data.len();
-    for i in 1..min(6, data.len()) + 1 {
-        if data[data.len() - i] != b'=' {
-            break;
-        }
-        unpadded_data_length -= 1;
-    }
-    let output_length = unpadded_data_length * 5 / 8;
-    let mut ret = Vec::with_capacity((output_length + 4) / 5 * 5);
-    for chunk in data.chunks(8) {
-        let buf = {
-            let mut buf = [0u8; 8];
-            for (i, &c) in chunk.iter().enumerate() {
-                match alphabet.get(c.wrapping_sub(b'0') as usize) {
-                    Some(&-1) | None => return None,
-                    Some(&value) => buf[i] = value as u8,
-                };
+
+    #[test]
+    fn non_ascii() {
+        let config = Config::new(Rfc4648 { padding: true });
+        assert_eq!(
+            config.decode("MZX\u{ff37}6==="),
+            Err(DecodeError::NonAscii { index: 3 })
+        );
+        assert_eq!(
+            config.decode("MZXW6\u{201c}=="),
+            Err(DecodeError::NonAscii { index: 5 })
+        );
+        let config = config.ignore_whitespace(true);
+        assert_eq!(
+            config.decode("MZ XW\u{ff16}==="),
+            Err(DecodeError::NonAscii { index: 5 })
+        );
+    }
+
+    #[test]
+    fn inverse_tables_match_symbols() {
+        // Rebuilds the inverse of `table` from its alphabet as written in the specification
+        // and the extra bytes the decoder accepts, as (byte, symbol it decodes as).
+        fn check(table: &CustomAlphabet, symbols: &[u8; 32], aliases: &[(u8, u8)]) {
+            assert_eq!(table.symbols(), symbols);
+            let mut inverse = [-1i8; 256];
+            for (value, &c) in symbols.iter().enumerate() {
+                inverse[c as usize] = value as i8;
             }
-            buf
-        };
-        ret.push((buf[0] << 3) | (buf[1] >> 2));
-        ret.push((buf[1] << 6) | (buf[2] << 1) | (buf[3] >> 4));
-        ret.push((buf[3] << 4) | (buf[4] >> 1));
-        ret.push((buf[4] << 7) | (buf[5] << 2) | (buf[6] >> 3));
-        ret.push((buf[6] << 5) | buf[7]);
+            for &(alias, symbol) in aliases {
+                inverse[alias as usize] = inverse[symbol as usize];
+            }
+            assert_eq!(
+                &table.values()[..],
+                &inverse[..],
+                "{:?}",
+                core::str::from_utf8(symbols)
+            );
+        }
+
+        let mut crockford_aliases = Vec::new();
+        for &(alias, symbol) in &[(b'I', b'1'), (b'L', b'1'), (b'O', b'0')] {
+            crockford_aliases.push((alias, symbol));
+            crockford_aliases.push((alias.to_ascii_lowercase(), symbol));
+        }
+        for c in (b'a'..=b'z').filter(|c| !b"ilou".contains(c)) {
+            crockford_aliases.push((c, c.to_ascii_uppercase()));
+        }
+        check(
+            &super::CROCKFORD,
+            b"0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+            &crockford_aliases,
+        );
+        check(&super::RFC4648, b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567", &[]);
+        check(
+            &super::RFC4648_LOWER,
+            b"abcdefghijklmnopqrstuvwxyz234567",
+            &[],
+        );
+        #[cfg(feature = "hex")]
+        {
+            check(
+                &super::RFC4648_HEX,
+                b"0123456789ABCDEFGHIJKLMNOPQRSTUV",
+                &[],
+            );
+            check(
+                &super::RFC4648_HEX_LOWER,
+                b"0123456789abcdefghijklmnopqrstuv",
+                &[],
+            );
+        }
+        #[cfg(feature = "zbase32")]
+        check(&super::Z, b"ybndrfg8ejkmcpqxot1uwisza345h769", &[]);
+        #[cfg(feature = "bech32")]
+        check(&super::BECH32, b"qpzry9x8gf2tvdw0s3jn54khce6mua7l", &[]);
+
+        // `U` is left out of Crockford's alphabet on purpose and must not decode.
+        assert_eq!(super::CROCKFORD.values()[b'U' as usize], -1);
+        assert_eq!(super::CROCKFORD.values()[b'u' as usize], -1);
     }
-    ret.truncate(output_length);
-    Some(ret)
-}
 
-#[cfg(test)]
-#[allow(dead_code, unused_attributes)]
-mod test {
-    use super::Alphabet::{Crockford, Rfc4648, Rfc4648Hex, Rfc4648HexLower, Rfc4648Lower, Z};
-    use super::{decode, encode};
-    use alloc::string::String;
-    use alloc::vec::Vec;
-    use core::fmt::{Debug, Error, Formatter};
-    use quickcheck::{Arbitrary, Gen};
+    #[test]
+    fn excluded_symbols() {
+        let config = Config::new(Crockford).excluded_symbols(true);
+        assert_eq!(
+            config.decode("CSQPYRK1EU"),
+            Err(DecodeError::ExcludedSymbol {
+                index: 9,
+                byte: b'U'
+            })
+        );
+        assert_eq!(
+            config.ignore_whitespace(true).decode("CSQP u"),
+            Err(DecodeError::ExcludedSymbol {
+                index: 5,
+                byte: b'u'
+            })
+        );
+        assert_eq!(config.decode("CSQPYRK1E8").unwrap(), b"foobar");
+        assert_eq!(
+            config.decode("CSQPYRK1E!"),
+            Err(DecodeError::InvalidChar {
+                index: 9,
+                byte: b'!'
+            })
+        );
+        // Off by default, and only about Crockford.
+        assert_eq!(
+            Config::new(Crockford).decode("U0"),
+            Err(DecodeError::InvalidChar {
+                index: 0,
+                byte: b'U'
+            })
+        );
+        #[cfg(feature = "zbase32")]
+        assert_eq!(
+            Config::new(Z).excluded_symbols(true).decode("vy"),
+            Err(DecodeError::InvalidChar {
+                index: 0,
+                byte: b'v'
+            })
+        );
+    }
 
-    #[derive(Clone)]
-    struct B32 {
-        c: u8,
+    #[test]
+    fn alphabet_differences() {
+        let diff = alphabet_diff(Rfc4648 { padding: true }, Crockford);
+        assert_eq!(diff.len(), 32);
+        assert_eq!(diff[..3], [(b'A', b'0'), (b'B', b'1'), (b'C', b'2')]);
+        assert_eq!(diff[31], (b'7', b'Z'));
+        for &(x, y) in &diff {
+            assert_eq!(value(Rfc4648 { padding: true }, x), value(Crockford, y));
+        }
+        // Padding is not part of the alphabet.
+        assert!(alphabet_diff(Rfc4648 { padding: true }, Rfc4648 { padding: false }).is_empty());
+        // Only the letters differ, the digits 2-7 are shared.
+        let diff = alphabet_diff(Rfc4648 { padding: false }, Rfc4648Lower { padding: false });
+        assert_eq!(diff.len(), 26);
+        assert!(diff.iter().all(|&(x, y)| x.to_ascii_lowercase() == y));
+        #[cfg(feature = "hex")]
+        assert_eq!(
+            alphabet_diff(Rfc4648Hex { padding: false }, Crockford),
+            [
+                (b'I', b'J'),
+                (b'J', b'K'),
+                (b'K', b'M'),
+                (b'L', b'N'),
+                (b'M', b'P'),
+                (b'N', b'Q'),
+                (b'O', b'R'),
+                (b'P', b'S'),
+                (b'Q', b'T'),
+                (b'R', b'V'),
+                (b'S', b'W'),
+                (b'T', b'X'),
+                (b'U', b'Y'),
+                (b'V', b'Z'),
+            ]
+        );
     }
 
-    impl Arbitrary for B32 {
-        fn arbitrary(g: &mut Gen) -> B32 {
-            B32 {
-                c: *g.choose(b"0123456789ABCDEFGHJKMNPQRSTVWXYZ").unwrap(),
-            }
+    #[test]
+    fn encode_to_slice_matches_encode() {
+        use alloc::vec;
+
+        fn test(data: Vec<u8>, padding: bool, newline: bool, spare: u8) -> bool {
+            let config = Config::new(Crockford)
+                .padding(padding)
+                .trailing_newline(newline);
+            let expected = config.encode(&data);
+            let mut out = vec![b'!'; expected.len() + spare as usize % 4];
+            let len = config.encode_to_slice(&data, &mut out).unwrap();
+            len == expected.len()
+                && out[..len] == *expected.as_bytes()
+                && out[len..].iter().all(|&b| b == b'!')
         }
+        quickcheck::quickcheck(test as fn(Vec<u8>, bool, bool, u8) -> bool);
+
+        let config = Config::new(Rfc4648 { padding: true });
+        let mut out = [b'!'; 7];
+        assert_eq!(
+            config.encode_to_slice(b"f", &mut out),
+            Err(Base32Error::BufferTooSmall { needed: 8, got: 7 })
+        );
+        assert_eq!(out, [b'!'; 7]);
+        assert_eq!(config.encode_to_slice(b"", &mut []), Ok(0));
     }
 
-    impl Debug for B32 {
-        fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-            (self.c as char).fmt(f)
+    #[test]
+    fn base32_error() {
+        use alloc::string::ToString;
+        use core::error::Error;
+
+        let err = DecodeError::InvalidLength { length: 1 };
+        let unified = Base32Error::from(err);
+        assert_eq!(unified, Base32Error::Decode(err));
+        assert_eq!(unified.to_string(), err.to_string());
+        assert!(unified.source().is_some());
+        assert!(Base32Error::InvalidAlphabet.source().is_none());
+        assert_eq!(
+            Base32Error::BufferTooSmall { needed: 8, got: 7 }.to_string(),
+            "output buffer too small: 8 bytes needed, 7 available"
+        );
+        assert_eq!(
+            Base32Error::ConflictingByte { byte: b'0' }.to_string(),
+            "byte '0' is a symbol or the padding of the alphabet, or not ASCII"
+        );
+    }
+
+    #[test]
+    fn branchless_matches_decode() {
+        fn test(data: Vec<u8>, noise: Option<(usize, u8)>) -> bool {
+            let alphabets = [
+                Crockford,
+                Rfc4648 { padding: true },
+                Rfc4648Lower { padding: false },
+                #[cfg(feature = "hex")]
+                Rfc4648Hex { padding: true },
+                #[cfg(feature = "zbase32")]
+                Z,
+                #[cfg(feature = "bech32")]
+                Bech32,
+            ];
+            alphabets.iter().all(|&alphabet| {
+                let mut encoded = encode(alphabet, &data).into_bytes();
+                if let Some((index, c)) = noise {
+                    let len = encoded.len() + 1;
+                    encoded.insert(index % len, c & 0x7F);
+                }
+                let encoded = String::from_utf8(encoded).unwrap();
+                decode_branchless(alphabet, &encoded) == Config::new(alphabet).decode(&encoded)
+            })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, Option<(usize, u8)>) -> bool);
+
+        for input in ["", "=", "MZXW6===", "MZ=W6===", "MZXW6=", "é", "M", "mzxw6==="] {
+            assert_eq!(
+                decode_branchless(Rfc4648 { padding: true }, input),
+                Config::new(Rfc4648 { padding: true }).decode(input),
+                "{:?}",
+                input
+            );
         }
     }
 
     #[test]
-    fn masks_crockford() {
+    fn encode_cased_round_trip() {
+        fn test(data: Vec<u8>) -> bool {
+            let alphabets = [
+                Crockford,
+                Rfc4648 { padding: true },
+                Rfc4648Lower { padding: false },
+                #[cfg(feature = "hex")]
+                Rfc4648Hex { padding: true },
+                #[cfg(feature = "hex")]
+                Rfc4648HexLower { padding: false },
+                #[cfg(feature = "zbase32")]
+                Z,
+            ];
+            alphabets.iter().all(|&alphabet| {
+                let config = Config::new(alphabet).case_insensitive(true);
+                let upper = encode_cased(alphabet, &data, true);
+                let lower = encode_cased(alphabet, &data, false);
+                !upper.bytes().any(|c| c.is_ascii_lowercase())
+                    && !lower.bytes().any(|c| c.is_ascii_uppercase())
+                    && upper.eq_ignore_ascii_case(&encode(alphabet, &data))
+                    && config.decode(&upper).unwrap() == data
+                    && config.decode(&lower).unwrap() == data
+            })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+
+        #[cfg(feature = "zbase32")]
+        assert_eq!(encode_cased(Z, b"foo", true), "C3ZS6");
         assert_eq!(
-            encode(Crockford, &[0xF8, 0x3E, 0x0F, 0x83, 0xE0]),
-            "Z0Z0Z0Z0"
+            decode(Crockford, &encode_cased(Crockford, b"foo", false)).unwrap(),
+            b"foo"
         );
+    }
+
+    #[test]
+    fn normalize_borrows_canonical() {
+        fn test(data: Vec<u8>) -> bool {
+            let configs = [
+                Config::new(Crockford),
+                Config::new(Rfc4648 { padding: true }),
+                #[cfg(feature = "zbase32")]
+                Config::new(Z).padding(true),
+                #[cfg(feature = "hex")]
+                Config::new(Rfc4648Hex { padding: false }).bit_order(BitOrder::LsbFirst),
+            ];
+            configs.iter().all(|config| {
+                let encoded = config.encode(&data);
+                matches!(config.normalize(&encoded), Ok(Cow::Borrowed(s)) if s == encoded)
+            })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn decode_and_canonicalize_matches_normalize() {
+        fn test(data: String, lsb_first: bool) -> bool {
+            let bit_order = if lsb_first {
+                BitOrder::LsbFirst
+            } else {
+                BitOrder::MsbFirst
+            };
+            let config = Config::new(Rfc4648 { padding: true })
+                .case_insensitive(true)
+                .bit_order(bit_order);
+            match config.decode_and_canonicalize(&data) {
+                Ok((bytes, canonical)) => {
+                    config.decode(&data).as_ref() == Ok(&bytes)
+                        && config.normalize(&data).as_deref() == Ok(&*canonical)
+                        && config.encode(&bytes) == canonical
+                }
+                Err(error) => config.decode(&data) == Err(error),
+            }
+        }
+        quickcheck::quickcheck(test as fn(String, bool) -> bool);
         assert_eq!(
-            encode(Crockford, &[0x07, 0xC1, 0xF0, 0x7C, 0x1F]),
-            "0Z0Z0Z0Z"
+            decode_and_canonicalize(Crockford, "CSQPY"),
+            Ok((b"foo".to_vec(), String::from("CSQPY")))
+        );
+    }
+
+    #[test]
+    fn normalize_owned() {
+        let config = Config::new(Rfc4648 { padding: true });
+        // Missing padding, lowercase, whitespace, percent-encoded padding and nonzero unused
+        // bits are all rewritten.
+        let inputs = [
+            "MZXW6",
+            "mzxw6===",
+            "MZ XW6===",
+            "MZXW6%3D%3D%3D",
+            "MZXW7===",
+        ];
+        for &input in &inputs {
+            let config = config
+                .case_insensitive(true)
+                .ignore_whitespace(true)
+                .percent_encoded_padding(true);
+            match config.normalize(input) {
+                Ok(Cow::Owned(normalized)) => assert_eq!(normalized, "MZXW6===", "{}", input),
+                other => panic!("{:?} normalized to {:?}", input, other),
+            }
+        }
+        assert!(matches!(config.normalize(""), Ok(Cow::Borrowed(""))));
+        assert_eq!(config.normalize("MZXW6==="), Ok(Cow::Borrowed("MZXW6===")));
+        assert_eq!(
+            config.normalize("MZXW!==="),
+            Err(DecodeError::InvalidChar {
+                index: 4,
+                byte: b'!'
+            })
         );
+        assert_eq!(normalize(Crockford, "ABCDEU"), None);
+
+        let lsb = config.bit_order(BitOrder::LsbFirst);
+        let encoded = lsb.encode(b"f");
+        assert!(matches!(lsb.normalize(&encoded), Ok(Cow::Borrowed(_))));
+        let mut dirty = encoded.into_bytes();
+        dirty[1] = b'7';
+        let dirty = String::from_utf8(dirty).unwrap();
+        assert!(matches!(lsb.normalize(&dirty), Ok(Cow::Owned(_))));
+    }
+
+    #[test]
+    fn decode_partial_prefix() {
+        fn test(data: Vec<u8>, at: usize, whitespace: bool) -> bool {
+            let config = Config::new(Rfc4648 { padding: true }).ignore_whitespace(whitespace);
+            let mut encoded = config.encode(&data).into_bytes();
+            let padding = encoded.iter().filter(|&&c| c == b'=').count();
+            if encoded.len() == padding {
+                return true;
+            }
+            let at = at % (encoded.len() - padding);
+            encoded[at] = b'!';
+            if whitespace {
+                encoded.insert(0, b' ');
+            }
+            let (prefix, error) = config.decode_partial(core::str::from_utf8(&encoded).unwrap());
+            prefix[..] == data[..at * 5 / 8]
+                && error
+                    == Some(DecodeError::InvalidChar {
+                        index: at + whitespace as usize,
+                        byte: b'!',
+                    })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize, bool) -> bool);
+
+        let config = Config::new(Rfc4648 { padding: true });
         assert_eq!(
-            decode(Crockford, "Z0Z0Z0Z0").unwrap(),
-            [0xF8, 0x3E, 0x0F, 0x83, 0xE0]
+            config.decode_partial("MY==!==="),
+            (
+                b"f".to_vec(),
+                Some(DecodeError::InvalidChar {
+                    index: 4,
+                    byte: b'!'
+                })
+            )
         );
         assert_eq!(
-            decode(Crockford, "0Z0Z0Z0Z").unwrap(),
-            [0x07, 0xC1, 0xF0, 0x7C, 0x1F]
+            config.strict_padding(true).decode_partial("MY="),
+            (
+                Vec::new(),
+                Some(DecodeError::InvalidPadding {
+                    expected: 6,
+                    actual: 1
+                })
+            )
         );
     }
 
     #[test]
-    fn masks_rfc4648() {
+    fn adversarial_input_does_not_panic() {
+        fn configs() -> Vec<Config> {
+            let mut configs = Vec::new();
+            let alphabets = [
+                Crockford,
+                Rfc4648 { padding: true },
+                Rfc4648Lower { padding: false },
+                #[cfg(feature = "hex")]
+                Rfc4648Hex { padding: true },
+                #[cfg(feature = "zbase32")]
+                Z,
+            ];
+            for &alphabet in &alphabets {
+                for &flags in &[0u8, 0b1111] {
+                    configs.push(
+                        Config::new(alphabet)
+                            .padding(flags & 1 != 0)
+                            .case_insensitive(flags & 2 != 0)
+                            .ignore_whitespace(flags & 4 != 0)
+                            .percent_encoded_padding(true)
+                            .strict_padding(flags & 8 != 0),
+                    );
+                }
+            }
+            configs
+        }
+        fn exercise(data: &str) {
+            for config in configs() {
+                let _ = config.decode(data);
+                let _ = config.decode_partial(data);
+                let _ = config.normalize(data);
+                let _ = config.decode_small::<8>(data);
+                let _ = config.decode_chars(data.chars());
+                let _ = config.encode(data.as_bytes());
+            }
+        }
+        fn test(data: String) -> bool {
+            exercise(&data);
+            true
+        }
+        quickcheck::quickcheck(test as fn(String) -> bool);
+
+        let inputs = [
+            "",
+            "=",
+            "========",
+            "================",
+            "=======A",
+            "A=======",
+            "%3D",
+            "%3",
+            "\u{e9}",
+            "MZXW6\u{e9}==",
+            "====\u{e9}===",
+            "\u{1f600}=======",
+            " ",
+            "\n=\t",
+        ];
+        for input in &inputs {
+            exercise(input);
+        }
+        let huge = "A".repeat(1 << 20) + "=";
+        exercise(&huge);
+    }
+
+    #[test]
+    fn all_padding() {
+        let config = Config::new(Rfc4648 { padding: true });
+        let strict = config.strict_padding(true);
+        for n in 0..20 {
+            let input = "=".repeat(n);
+            let decoded = config.decode_detailed(&input).unwrap();
+            assert_eq!(decoded.data, b"", "{}", input);
+            assert_eq!(decoded.actual_padding, n);
+            assert_eq!(config.decode_chars(input.chars()).unwrap(), b"");
+            let expected = if n == 0 {
+                Ok(Vec::new())
+            } else {
+                Err(DecodeError::InvalidPadding {
+                    expected: 0,
+                    actual: n,
+                })
+            };
+            assert_eq!(strict.decode(&input), expected);
+        }
+
+        // One symbol carries no whole byte, and no encoding ends in 1, 3 or 6 symbols.
+        assert_eq!(config.decode("A=======").unwrap(), b"");
         assert_eq!(
-            encode(Rfc4648 { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
-            "7A7H7A7H"
+            strict.decode("A======="),
+            Err(DecodeError::InvalidLength { length: 1 })
         );
+        assert_eq!(config.decode("MY========").unwrap(), b"f");
         assert_eq!(
-            encode(Rfc4648 { padding: false }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
-            "O7A7O7A7"
+            strict.decode("MY========"),
+            Err(DecodeError::InvalidPadding {
+                expected: 6,
+                actual: 8
+            })
         );
+        assert_eq!(strict.decode("MY======").unwrap(), b"f");
         assert_eq!(
-            decode(Rfc4648 { padding: false }, "7A7H7A7H").unwrap(),
-            [0xF8, 0x3E, 0x7F, 0x83, 0xE7]
+            config.percent_encoded_padding(true).decode("%3D=%3D=====").unwrap(),
+            b""
         );
         assert_eq!(
-            decode(Rfc4648 { padding: false }, "O7A7O7A7").unwrap(),
-            [0x77, 0xC1, 0xF7, 0x7C, 0x1F]
+            Config::new(Rfc4648 { padding: false }).decode("========"),
+            Err(DecodeError::InvalidChar {
+                index: 0,
+                byte: b'='
+            })
         );
+    }
+
+    #[test]
+    fn lengths_do_not_overflow() {
+        // The longest possible slice leaves 2 bytes after the last full group on both 32- and
+        // 64-bit targets, and the longest symbol count 7 symbols (4 bytes).
         assert_eq!(
-            encode(Rfc4648 { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83]),
-            "7A7H7AY"
+            unpadded_len(isize::MAX as usize),
+            isize::MAX as usize / 5 * 8 + 4
         );
+        assert_eq!(decoded_len(usize::MAX), usize::MAX / 8 * 5 + 4);
+        assert_eq!(unpadded_len(usize::MAX), usize::MAX);
+
+        // The largest input whose encoding fits in a `String`, and the first that does not.
+        let max = isize::MAX as usize / 8 * 5;
+        for padding in [false, true] {
+            let config = Config::new(Rfc4648 { padding }).trailing_newline(true);
+            assert_eq!(config.encoded_len(max), isize::MAX as usize / 8 * 8 + 1);
+            assert!(config.encoded_len(max + 5) > isize::MAX as usize);
+            assert_eq!(config.encoded_len(usize::MAX), usize::MAX);
+            assert_eq!(config.encoded_len(usize::MAX - 4), usize::MAX);
+        }
+        for len in 0..100 {
+            assert_eq!(unpadded_len(len), (len * 8).div_ceil(5));
+            assert_eq!(decoded_len(len), len * 5 / 8);
+        }
     }
 
     #[test]
-    fn masks_rfc4648_pad() {
+    fn validate_batch_in_order() {
+        let items = ["MZXW6===", "MZXW6!==", "", "MZXW6YQ", "M"];
+        let results = validate_batch(Rfc4648 { padding: true }, &items);
+        assert_eq!(results.len(), items.len());
+        for (item, result) in items.iter().zip(results) {
+            assert_eq!(result, Config::new(Rfc4648 { padding: true }).decode(item).map(drop));
+        }
+        assert_eq!(validate_batch(Crockford, &[]), []);
+    }
+
+    #[test]
+    fn decode_trimmed_markers() {
+        let alphabet = Rfc4648 { padding: true };
         assert_eq!(
-            encode(Rfc4648 { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
-            "7A7H7A7H"
+            decode_trimmed(alphabet, "<MZXW6YTBOI======>", "<", ">").unwrap(),
+            b"foobar"
         );
+        assert_eq!(decode_trimmed(alphabet, "<>", "<", ">").unwrap(), b"");
         assert_eq!(
-            encode(Rfc4648 { padding: true }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
-            "O7A7O7A7"
+            decode_trimmed(alphabet, "MZXW6YTBOI======>", "<", ">"),
+            Err(DecodeError::MissingPrefix)
         );
         assert_eq!(
-            decode(Rfc4648 { padding: true }, "7A7H7A7H").unwrap(),
-            [0xF8, 0x3E, 0x7F, 0x83, 0xE7]
+            decode_trimmed(alphabet, "<MZXW6YTBOI======", "<", ">"),
+            Err(DecodeError::MissingSuffix)
         );
         assert_eq!(
-            decode(Rfc4648 { padding: true }, "O7A7O7A7").unwrap(),
-            [0x77, 0xC1, 0xF7, 0x7C, 0x1F]
+            decode_trimmed(alphabet, "MZXW6YTBOI======", "<", ">"),
+            Err(DecodeError::MissingPrefix)
         );
+        // The markers may not overlap.
         assert_eq!(
-            encode(Rfc4648 { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83]),
-            "7A7H7AY="
+            decode_trimmed(alphabet, "<", "<", "<"),
+            Err(DecodeError::MissingSuffix)
+        );
+        assert_eq!(
+            decode_trimmed(alphabet, "b32:MZ!W6===", "b32:", ""),
+            Err(DecodeError::InvalidChar {
+                index: 6,
+                byte: b'!'
+            })
         );
     }
 
     #[test]
-    fn masks_rfc4648_lower() {
+    fn decode_any_priority() {
+        fn name(alphabet: super::Alphabet) -> &'static str {
+            match alphabet {
+                Rfc4648 { padding: true } => "rfc4648 padded",
+                Rfc4648 { padding: false } => "rfc4648",
+                Rfc4648Lower { padding: true } => "rfc4648 lower padded",
+                Rfc4648Lower { padding: false } => "rfc4648 lower",
+                #[cfg(feature = "hex")]
+                Rfc4648Hex { padding: true } => "hex padded",
+                #[cfg(feature = "hex")]
+                Rfc4648Hex { padding: false } => "hex",
+                #[cfg(feature = "hex")]
+                Rfc4648HexLower { .. } => "hex lower",
+                Crockford => "crockford",
+                #[cfg(feature = "zbase32")]
+                Z => "z",
+                #[cfg(feature = "bech32")]
+                Bech32 => "bech32",
+                Custom(_) => "custom",
+            }
+        }
+        let cases = [
+            ("MZXW6===", "rfc4648 padded", &b"foo"[..]),
+            ("MZXW6", "rfc4648", b"foo"),
+            ("mzxw6===", "rfc4648 lower padded", b"foo"),
+            // Valid hex, but also valid RFC4648 with a different meaning.
+            ("CPNMU===", "rfc4648 padded", b"\x13\xda\xca"),
+            #[cfg(feature = "hex")]
+            ("00======", "hex padded", b"\x00"),
+            #[cfg(feature = "hex")]
+            ("00", "hex", b"\x00"),
+            ("csqpyrk1e8", "crockford", b"foobar"),
+            ("CSQPYRK1E8", "crockford", b"foobar"),
+            ("c3zs6", "rfc4648 lower", b"\x16\xf3\x2f"),
+            // Only z-base-32 has u as well as 8 and y.
+            #[cfg(feature = "zbase32")]
+            ("y8uy", "z", b"\x01\xe6"),
+        ];
+        for &(input, expected, data) in &cases {
+            let (alphabet, decoded) = decode_any(input).unwrap();
+            assert_eq!(
+                (name(alphabet), &decoded[..]),
+                (expected, data),
+                "{}",
+                input
+            );
+        }
+        for &input in &["MZXW6=", "!", "MZXW6\u{e9}"] {
+            assert!(matches!(
+                decode_any(input),
+                Err(DecodeError::NoMatchingAlphabet)
+            ));
+        }
+    }
+
+    #[cfg(feature = "bech32")]
+    #[test]
+    fn bech32_vectors() {
+        // Witness programs from the BIP 173 test vectors and their data parts, without the
+        // leading witness version symbol and the trailing six checksum symbols.
+        let vectors: &[(&[u8], &str)] = &[
+            (
+                &[
+                    0x75, 0x1e, 0x76, 0xe8, 0x19, 0x91, 0x96, 0xd4, 0x54, 0x94, 0x1c, 0x45, 0xd1,
+                    0xb3, 0xa3, 0x23, 0xf1, 0x43, 0x3b, 0xd6,
+                ],
+                "w508d6qejxtdg4y5r3zarvary0c5xw7k",
+            ),
+            (
+                &[
+                    0x18, 0x63, 0x14, 0x3c, 0x14, 0xc5, 0x16, 0x68, 0x04, 0xbd, 0x19, 0x20, 0x33,
+                    0x56, 0xda, 0x13, 0x6c, 0x98, 0x56, 0x78, 0xcd, 0x4d, 0x27, 0xa1, 0xb8, 0xc6,
+                    0x32, 0x96, 0x04, 0x90, 0x32, 0x62,
+                ],
+                "rp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q",
+            ),
+            (&[0x75, 0x1e], "w50q"),
+        ];
+        for &(data, encoded) in vectors {
+            assert_eq!(encode(Bech32, data), encoded);
+            assert_eq!(decode(Bech32, encoded).unwrap(), data);
+        }
+        for length in 0..16 {
+            let decoded = decode(Bech32, &"q".repeat(length));
+            assert_eq!(
+                decoded.is_some(),
+                ![1, 3, 6].contains(&(length % 8)),
+                "{}",
+                length
+            );
+        }
+        assert_eq!(decode(Bech32, "b"), None);
+        assert_eq!(decode(Bech32, "W508"), None);
+        assert_eq!(Bech32.canonical_case(), Case::Lower);
+    }
+
+    #[test]
+    fn block_aligned_round_trip() {
+        fn test(data: Vec<u8>) -> bool {
+            let alphabets = [
+                Crockford,
+                Rfc4648 { padding: true },
+                #[cfg(feature = "zbase32")]
+                Z,
+            ];
+            alphabets.iter().all(|&alphabet| {
+                let encoded = encode_block_aligned(alphabet, &data, b'-').unwrap();
+                encoded.len().is_multiple_of(8)
+                    && encoded.trim_end_matches('-')
+                        == encode(alphabet, &data).trim_end_matches('=')
+                    && decode_block_aligned(alphabet, &encoded, b'-').unwrap() == data
+            })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+
         assert_eq!(
-            encode(Rfc4648Lower { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
-            "7a7h7a7h"
+            encode_block_aligned(Rfc4648 { padding: true }, b"f", b'.').unwrap(),
+            "MY......"
         );
         assert_eq!(
-            encode(Rfc4648Lower { padding: false }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
-            "o7a7o7a7"
+            decode_block_aligned(Crockford, "CSQPY**", b'*'),
+            Err(Base32Error::Decode(DecodeError::InvalidLength { length: 7 }))
         );
         assert_eq!(
-            decode(Rfc4648Lower { padding: false }, "7a7h7a7h").unwrap(),
-            [0xF8, 0x3E, 0x7F, 0x83, 0xE7]
+            decode_block_aligned(Crockford, "CS*PY***", b'*'),
+            Err(Base32Error::Decode(DecodeError::InvalidChar {
+                index: 2,
+                byte: b'*'
+            }))
+        );
+    }
+
+    #[test]
+    fn block_aligned_conflicting_fill() {
+        // Symbols, including Crockford's aliases and lowercase, and non-ASCII bytes.
+        for &fill in &[b'0', b'O', b'o', b'Z', 0x80, 0xFF] {
+            let error = Base32Error::ConflictingByte { byte: fill };
+            assert_eq!(encode_block_aligned(Crockford, b"foo", fill), Err(error));
+            assert_eq!(decode_block_aligned(Crockford, "CSQPY000", fill), Err(error));
+        }
+        assert_eq!(
+            encode_block_aligned(Rfc4648 { padding: true }, b"f", b'A'),
+            Err(Base32Error::ConflictingByte { byte: b'A' })
+        );
+        // `=` is not a symbol once padding is off, so it can be the fill.
+        assert_eq!(
+            encode_block_aligned(Rfc4648 { padding: true }, b"f", b'=').unwrap(),
+            "MY======"
         );
+    }
+
+    #[test]
+    fn every_single_byte() {
+        let alphabets = [
+            (Crockford, "0123456789ABCDEFGHJKMNPQRSTVWXYZ", ""),
+            (
+                Rfc4648 { padding: true },
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+                "======",
+            ),
+            (
+                Rfc4648 { padding: false },
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+                "",
+            ),
+            (
+                Rfc4648Lower { padding: true },
+                "abcdefghijklmnopqrstuvwxyz234567",
+                "======",
+            ),
+            #[cfg(feature = "hex")]
+            (
+                Rfc4648Hex { padding: true },
+                "0123456789ABCDEFGHIJKLMNOPQRSTUV",
+                "======",
+            ),
+            #[cfg(feature = "hex")]
+            (
+                Rfc4648HexLower { padding: false },
+                "0123456789abcdefghijklmnopqrstuv",
+                "",
+            ),
+            #[cfg(feature = "zbase32")]
+            (Z, "ybndrfg8ejkmcpqxot1uwisza345h769", ""),
+            #[cfg(feature = "bech32")]
+            (Bech32, "qpzry9x8gf2tvdw0s3jn54khce6mua7l", ""),
+        ];
+        for &(alphabet, symbols, padding) in &alphabets {
+            let symbols = symbols.as_bytes();
+            for b in 0..=255u8 {
+                // The top five bits, then the low three followed by two zero bits.
+                let mut expected = String::new();
+                expected.push(symbols[(b >> 3) as usize] as char);
+                expected.push(symbols[((b & 7) << 2) as usize] as char);
+                expected.push_str(padding);
+
+                let encoded = encode(alphabet, &[b]);
+                assert_eq!(encoded, expected);
+                assert_eq!(decode(alphabet, &encoded).unwrap(), [b], "{:?}", encoded);
+            }
+        }
+    }
+
+    #[test]
+    fn valid_chars() {
+        let cases = [
+            (
+                Rfc4648 { padding: true },
+                "AZaz27",
+                "0189@[`{=\u{0}\u{e9}\u{212a}",
+            ),
+            #[cfg(feature = "hex")]
+            (Rfc4648Hex { padding: false }, "09AVav", "WwXx/:=\u{ff10}"),
+            (Crockford, "09AZazIiLlOo", "Uu=\u{0}-"),
+            #[cfg(feature = "zbase32")]
+            (Z, "yb19YB", "02lvLV="),
+        ];
+        for &(alphabet, valid, invalid) in &cases {
+            for c in valid.chars() {
+                assert!(is_valid_char(alphabet, c), "{:?}", c);
+            }
+            for c in invalid.chars() {
+                assert!(!is_valid_char(alphabet, c), "{:?}", c);
+            }
+        }
+    }
+
+    /// A deliberately naive RFC4648 encoder, one bit at a time, to check the real one against.
+    fn reference_encode(data: &[u8], padding: bool) -> String {
+        const SYMBOLS: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        let mut bits = Vec::new();
+        for &byte in data {
+            for i in (0..8).rev() {
+                bits.push(byte >> i & 1);
+            }
+        }
+        let mut ret = String::new();
+        for group in bits.chunks(5) {
+            let mut value = 0;
+            for i in 0..5 {
+                value = value << 1 | group.get(i).copied().unwrap_or(0);
+            }
+            ret.push(SYMBOLS[value as usize] as char);
+        }
+        while padding && !ret.len().is_multiple_of(8) {
+            ret.push('=');
+        }
+        ret
+    }
+
+    /// The inverse of `reference_encode`, dropping the bits left over after the last byte.
+    fn reference_decode(data: &str) -> Vec<u8> {
+        const SYMBOLS: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        let mut bits = Vec::new();
+        for c in data.bytes().filter(|&c| c != b'=') {
+            let value = SYMBOLS.iter().position(|&s| s == c).unwrap() as u8;
+            for i in (0..5).rev() {
+                bits.push(value >> i & 1);
+            }
+        }
+        bits.chunks_exact(8)
+            .map(|byte| byte.iter().fold(0, |acc, &bit| acc << 1 | bit))
+            .collect()
+    }
+
+    #[test]
+    fn matches_reference() {
+        fn test(data: Vec<u8>, padding: bool) -> bool {
+            let alphabet = Rfc4648 { padding };
+            let encoded = encode(alphabet, &data);
+            encoded == reference_encode(&data, padding)
+                && reference_decode(&encoded) == data
+                && decode(alphabet, &encoded).unwrap() == reference_decode(&encoded)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, bool) -> bool);
+
+        // Decoding symbols that were not produced by the encoder, including nonzero bits after
+        // the last byte.
+        fn test_decode(symbols: Vec<u8>) -> bool {
+            const SYMBOLS: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+            let encoded = symbols
+                .iter()
+                .map(|&s| SYMBOLS[s as usize % 32] as char)
+                .collect::<String>();
+            match decode(Rfc4648 { padding: false }, &encoded) {
+                Some(decoded) => decoded == reference_decode(&encoded),
+                None => matches!(encoded.len() % 8, 1 | 3 | 6),
+            }
+        }
+        quickcheck::quickcheck(test_decode as fn(Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn custom_matches_builtin() {
+        static HEX: CustomAlphabet = base32_alphabet!("0123456789ABCDEFGHIJKLMNOPQRSTUV");
+        for &(data, _, expected) in RFC4648_VECTORS {
+            let config = Config::new(Custom(&HEX)).padding(true);
+            assert_eq!(config.encode(data), expected);
+            assert_eq!(config.decode(expected).unwrap(), data);
+            #[cfg(feature = "hex")]
+            assert_eq!(
+                encode(Custom(&HEX), data),
+                encode(Rfc4648Hex { padding: false }, data)
+            );
+        }
+        assert_eq!(decode(Custom(&HEX), "W"), None);
+    }
+
+    #[test]
+    fn decode_fields_by_length() {
+        let alphabet = Rfc4648 { padding: true };
         assert_eq!(
-            decode(Rfc4648Lower { padding: false }, "o7a7o7a7").unwrap(),
-            [0x77, 0xC1, 0xF7, 0x7C, 0x1F]
+            decode_fields(alphabet, "MZXW6MFRGGMZXW6YTBOI", &[0, 5, 5, 10]).unwrap(),
+            [&b""[..], b"foo", b"abc", b"foobar"]
         );
         assert_eq!(
-            encode(Rfc4648Lower { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83]),
-            "7a7h7ay"
+            decode_fields(alphabet, "MZXW6MFRGG", &[5, 4]),
+            Err(DecodeError::UnexpectedLength {
+                got: 10,
+                expected: 9
+            })
         );
-    }
-
-    #[test]
-    fn masks_rfc4648_lower_pad() {
         assert_eq!(
-            encode(Rfc4648Lower { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
-            "7a7h7a7h"
+            decode_fields(alphabet, "MZXW6MFRGG", &[5, 6]),
+            Err(DecodeError::UnexpectedLength {
+                got: 10,
+                expected: 11
+            })
         );
         assert_eq!(
-            encode(Rfc4648Lower { padding: true }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
-            "o7a7o7a7"
+            decode_fields(alphabet, "MZXW6MFRGG", &[4, 6]),
+            Err(DecodeError::InvalidLength { length: 6 })
         );
+        // The lengths add up to 1 modulo 2^n.
         assert_eq!(
-            decode(Rfc4648Lower { padding: true }, "7a7h7a7h").unwrap(),
-            [0xF8, 0x3E, 0x7F, 0x83, 0xE7]
+            decode_fields(Crockford, "0", &[usize::MAX, 2]),
+            Err(DecodeError::UnexpectedLength {
+                got: 1,
+                expected: usize::MAX
+            })
         );
         assert_eq!(
-            decode(Rfc4648Lower { padding: true }, "o7a7o7a7").unwrap(),
-            [0x77, 0xC1, 0xF7, 0x7C, 0x1F]
+            decode_fields(alphabet, "MZXW6MF1GG", &[5, 5]),
+            Err(DecodeError::InvalidDigit {
+                index: 7,
+                byte: b'1'
+            })
         );
         assert_eq!(
-            encode(Rfc4648Lower { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83]),
-            "7a7h7ay="
+            decode_fields(alphabet, "MZ===MFRGG", &[5, 5]),
+            Err(DecodeError::InvalidChar {
+                index: 2,
+                byte: b'='
+            })
         );
     }
 
     #[test]
-    fn masks_rfc4648_hex() {
-        assert_eq!(
-            encode(Rfc4648Hex { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
-            "V0V7V0V7"
-        );
+    fn max_input_bytes_inverts_encoded_len() {
+        let configs = [
+            Config::new(Rfc4648 { padding: true }),
+            Config::new(Rfc4648 { padding: false }),
+            Config::new(Crockford).trailing_newline(true),
+            #[cfg(feature = "zbase32")]
+            Config::new(Z).padding(true).trailing_newline(true),
+        ];
+        for config in configs {
+            for char_len in 0..100 {
+                let max = config.max_input_bytes(char_len);
+                assert!(config.encoded_len(max) <= char_len || char_len < config.encoded_len(0));
+                assert!(config.encoded_len(max + 1) > char_len);
+            }
+        }
+
+        // Padded and unpadded only agree on whole groups.
+        let padded = [0, 0, 0, 0, 0, 0, 0, 0, 5, 5, 5, 5, 5, 5, 5, 5, 10];
+        let unpadded = [0, 0, 1, 1, 2, 3, 3, 4, 5, 5, 6, 6, 7, 8, 8, 9, 10];
+        for char_len in 0..=16 {
+            assert_eq!(max_input_bytes(Rfc4648 { padding: true }, char_len), padded[char_len]);
+            assert_eq!(max_input_bytes(Rfc4648 { padding: false }, char_len), unpadded[char_len]);
+        }
+
+        let padded = Config::new(Rfc4648 { padding: true });
+        assert_eq!(padded.max_input_bytes(usize::MAX), usize::MAX / 8 * 5);
+        assert_eq!(padded.padding(false).max_input_bytes(usize::MAX), decoded_len(usize::MAX));
+        assert_eq!(Config::new(Crockford).trailing_newline(true).max_input_bytes(0), 0);
+    }
+
+    #[test]
+    fn encoded_len_matches_encode() {
+        fn test(data: Vec<u8>) -> bool {
+            let alphabets = [
+                Crockford,
+                Rfc4648 { padding: true },
+                #[cfg(feature = "zbase32")]
+                Z,
+            ];
+            alphabets.iter().all(|&alphabet| {
+                let counts = encoded_char_count(alphabet, data.iter().copied());
+                encoded_len(alphabet, data.len()) == encode(alphabet, &data).len()
+                    && counts
+                        .enumerate()
+                        .all(|(i, count)| count == encode(alphabet, &data[..i + 1]).len())
+            })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool)
+    }
+
+    #[test]
+    fn empty_after_skipping() {
+        // Input that is nothing but skipped characters decodes like the empty string.
+        let crockford = Config::new(Crockford).ignore_whitespace(true);
+        let padded = Config::new(Rfc4648 { padding: true }).ignore_whitespace(true);
+        for data in ["", " ", " \r\n\t "] {
+            assert_eq!(crockford.decode(data).unwrap(), b"");
+            assert_eq!(crockford.decode_partial(data), (Vec::new(), None));
+            assert_eq!(crockford.decode_chars(data.chars()).unwrap(), b"");
+            assert_eq!(crockford.decode_small::<4>(data).unwrap().len(), 0);
+            assert_eq!(crockford.normalize(data).unwrap(), "");
+            assert_eq!(padded.decode(data).unwrap(), b"");
+            assert_eq!(padded.strict_padding(true).decode(data).unwrap(), b"");
+        }
+        for data in ["", "-", "----", " - -\n"] {
+            assert_eq!(decode_friendly(Crockford, data).unwrap(), b"");
+            assert_eq!(decode_friendly(Rfc4648 { padding: true }, data).unwrap(), b"");
+            assert_eq!(decode_ignoring(Crockford, data, b" -\n").unwrap(), b"");
+        }
+        for data in ["", "-", "----"] {
+            assert_eq!(regroup_crockford(data, 4).unwrap(), "");
+        }
+        assert_eq!(decode_friendly(Rfc4648 { padding: false }, "-- ==").unwrap(), b"");
+        assert_eq!(decode_dns_labels(Crockford, ["", ""]).unwrap(), b"");
+
+        // A license key keeps its check symbol, so hyphens alone are not a key.
+        assert_eq!(decode_license_key("----"), None);
+        assert_eq!(decode_license_key("---0").unwrap(), b"");
+        assert_eq!(decode_license_key("0").unwrap(), b"");
+    }
+
+    #[test]
+    fn decode_ignoring_separators() {
         assert_eq!(
-            encode(Rfc4648Hex { padding: false }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
-            "EV0VEV0V"
+            decode_ignoring(Rfc4648 { padding: true }, "MZXW-6YTB-OI==-====", b"-").unwrap(),
+            b"foobar"
         );
         assert_eq!(
-            decode(Rfc4648Hex { padding: false }, "7A7H7A7H").unwrap(),
-            [0x3A, 0x8F, 0x13, 0xA8, 0xF1]
+            decode_ignoring(Crockford, ":CSQPYRK1E8:", b".:").unwrap(),
+            b"foobar"
         );
         assert_eq!(
-            decode(Rfc4648Hex { padding: false }, "O7A7O7A7").unwrap(),
-            [0xC1, 0xD4, 0x7C, 0x1D, 0x47]
+            decode_ignoring(Crockford, "CS.QP.U", b"."),
+            Err(Base32Error::Decode(DecodeError::InvalidChar {
+                index: 6,
+                byte: b'U'
+            }))
         );
         assert_eq!(
-            encode(Rfc4648Hex { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83]),
-            "V0V7V0O"
+            decode_ignoring(Crockford, "CS QP", b"."),
+            Err(Base32Error::Decode(DecodeError::InvalidChar {
+                index: 2,
+                byte: b' '
+            }))
         );
     }
 
     #[test]
-    fn masks_rfc4648_hex_pad() {
+    fn decode_ignoring_conflicting() {
+        // A symbol, an alias, padding of a padded alphabet and a non-ASCII byte.
+        let cases = [
+            (Crockford, &b".:0"[..], b'0'),
+            (Crockford, &b"l"[..], b'l'),
+            (Rfc4648 { padding: true }, &b"-="[..], b'='),
+            (Crockford, &[0xC3][..], 0xC3),
+        ];
+        for &(alphabet, ignore, byte) in &cases {
+            assert_eq!(
+                decode_ignoring(alphabet, "CSQP", ignore),
+                Err(Base32Error::ConflictingByte { byte })
+            );
+        }
+        // `=` is only reserved where it is padding.
         assert_eq!(
-            encode(Rfc4648Hex { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
-            "V0V7V0V7"
+            decode_ignoring(Crockford, "CS=QP=YRK1E8", b"=").unwrap(),
+            b"foobar"
         );
+    }
+
+    #[test]
+    fn matches_bytes_compares_decoded() {
+        fn test(data: Vec<u8>, other: Vec<u8>) -> bool {
+            let encoded = encode(Rfc4648 { padding: true }, &data);
+            [matches_bytes, matches_bytes_constant_time]
+                .iter()
+                .all(|f| {
+                    f(Rfc4648 { padding: true }, &encoded, &data)
+                        && f(Rfc4648 { padding: true }, &encoded, &other) == (data == other)
+                })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, Vec<u8>) -> bool);
+
+        let data = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+        let mut other = data.clone();
+        other[999] ^= 1;
+        let encoded = encode(Crockford, &data);
+        assert!(matches_bytes(Crockford, &encoded, &data));
+        assert!(!matches_bytes(Crockford, &encoded, &other));
+        assert!(!matches_bytes_constant_time(Crockford, &encoded, &other));
+        #[cfg(feature = "zbase32")]
+        assert!(!matches_bytes_constant_time(Z, "y", b""));
+    }
+
+    #[test]
+    fn decode_exact_length() {
+        let alphabet = Rfc4648 { padding: true };
         assert_eq!(
-            encode(Rfc4648Hex { padding: true }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
-            "EV0VEV0V"
+            decode_exact(alphabet, "MZXW6YTBOI======", 6).unwrap(),
+            b"foobar"
         );
+        assert_eq!(decode_exact(alphabet, "", 0).unwrap(), b"");
         assert_eq!(
-            decode(Rfc4648Hex { padding: true }, "7A7H7A7H").unwrap(),
-            [0x3A, 0x8F, 0x13, 0xA8, 0xF1]
+            decode_exact(alphabet, "MZXW6YTB", 6),
+            Err(DecodeError::UnexpectedLength {
+                got: 5,
+                expected: 6
+            })
         );
         assert_eq!(
-            decode(Rfc4648Hex { padding: true }, "O7A7O7A7").unwrap(),
-            [0xC1, 0xD4, 0x7C, 0x1D, 0x47]
+            decode_exact(alphabet, "MZXW6YTBOI======", 5),
+            Err(DecodeError::UnexpectedLength {
+                got: 6,
+                expected: 5
+            })
         );
         assert_eq!(
-            encode(Rfc4648Hex { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83]),
-            "V0V7V0O="
+            decode_exact(alphabet, "MZXW6YTBO1======", 6),
+            Err(DecodeError::InvalidDigit {
+                index: 9,
+                byte: b'1'
+            })
         );
     }
 
     #[test]
-    fn masks_rfc4648_hex_lower() {
+    fn unpadded_exact_length() {
+        let alphabet = Rfc4648 { padding: true };
         assert_eq!(
-            encode(Rfc4648HexLower { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
-            "v0v7v0v7"
+            decode_unpadded_exact(alphabet, "MZXW6YTBOI", 6).unwrap(),
+            b"foobar"
         );
+        assert_eq!(decode_unpadded_exact(alphabet, "", 0).unwrap(), b"");
+        assert_eq!(decode_unpadded_exact(alphabet, "MZXW6YQ", 4).unwrap(), b"foob");
         assert_eq!(
-            encode(Rfc4648HexLower { padding: false }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
-            "ev0vev0v"
+            decode_unpadded_exact(alphabet, "MZXW6YTBOI======", 6),
+            Err(DecodeError::InvalidChar {
+                index: 10,
+                byte: b'='
+            })
         );
+        // Truncated to a length that is still valid and decodes to 5 bytes.
         assert_eq!(
-            decode(Rfc4648HexLower { padding: false }, "7a7h7a7h").unwrap(),
-            [0x3A, 0x8F, 0x13, 0xA8, 0xF1]
+            decode_unpadded_exact(alphabet, "MZXW6YTB", 6),
+            Err(DecodeError::LengthMismatch {
+                got: 5,
+                expected: 6
+            })
         );
         assert_eq!(
-            decode(Rfc4648HexLower { padding: false }, "o7a7o7a7").unwrap(),
-            [0xC1, 0xD4, 0x7C, 0x1D, 0x47]
+            decode_unpadded_exact(alphabet, "MZXW6YTBOI", 5),
+            Err(DecodeError::LengthMismatch {
+                got: 6,
+                expected: 5
+            })
         );
+        // One symbol too many for the byte, which Crockford does not reject by itself.
+        assert_eq!(decode_unpadded_exact(Crockford, "CR", 1).unwrap(), b"f");
         assert_eq!(
-            encode(Rfc4648HexLower { padding: false }, &[0xF8, 0x3E, 0x7F, 0x83]),
-            "v0v7v0o"
+            decode_unpadded_exact(Crockford, "CR0", 1),
+            Err(DecodeError::InvalidLength { length: 3 })
+        );
+        assert_eq!(
+            decode_unpadded_exact(alphabet, "MZXW6YTBO=", 6),
+            Err(DecodeError::InvalidChar {
+                index: 9,
+                byte: b'='
+            })
         );
+
+        fn test(data: Vec<u8>) -> bool {
+            let encoded = encode(Crockford, &data);
+            decode_unpadded_exact(Crockford, &encoded, data.len()).unwrap() == data
+                && decode_unpadded_exact(Crockford, &encoded, data.len() + 1).is_err()
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
     }
 
     #[test]
-    fn masks_rfc4648_hex_lower_pad() {
-        assert_eq!(
-            encode(Rfc4648HexLower { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83, 0xE7]),
-            "v0v7v0v7"
-        );
+    fn dns_labels() {
+        let alphabet = Rfc4648 { padding: true };
+        assert!(encode_dns_labels(alphabet, b"").is_empty());
+        assert_eq!(decode_dns_labels(alphabet, Vec::new()).unwrap(), b"");
+
+        // 200 bytes are 320 symbols: five full labels and one of 5 symbols.
+        let data = (0..200).map(|i| (i * 7) as u8).collect::<Vec<u8>>();
+        let labels = encode_dns_labels(alphabet, &data);
+        let lengths = labels.iter().map(String::len).collect::<Vec<usize>>();
+        assert_eq!(lengths, [63, 63, 63, 63, 63, 5]);
+        assert_eq!(labels.concat(), encode(alphabet, &data).to_ascii_lowercase());
+        assert_eq!(decode_dns_labels(alphabet, labels.iter().map(String::as_str)).unwrap(), data);
+
+        let name = labels.join(".") + ".example.";
+        let encoded = name.strip_suffix(".example.").unwrap().to_ascii_uppercase();
+        assert_eq!(decode_dns_labels(alphabet, encoded.split('.')).unwrap(), data);
+
         assert_eq!(
-            encode(Rfc4648HexLower { padding: true }, &[0x77, 0xC1, 0xF7, 0x7C, 0x1F]),
-            "ev0vev0v"
+            decode_dns_labels(alphabet, ["mzxw6", "ytb=i"]),
+            Err(DecodeError::InvalidChar {
+                index: 8,
+                byte: b'='
+            })
         );
+
+        fn test(data: Vec<u8>) -> bool {
+            let alphabets = [
+                Crockford,
+                Rfc4648 { padding: true },
+                #[cfg(feature = "hex")]
+                Rfc4648Hex { padding: false },
+                #[cfg(feature = "zbase32")]
+                Z,
+            ];
+            alphabets.iter().all(|&alphabet| {
+                let labels = encode_dns_labels(alphabet, &data);
+                labels.iter().all(|label| {
+                    !label.is_empty()
+                        && label.len() <= 63
+                        && !label.bytes().any(|c| c.is_ascii_uppercase())
+                }) && decode_dns_labels(alphabet, labels.iter().map(String::as_str)).unwrap()
+                    == data
+            })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn alphabet_regex_matches_symbols() {
+        // Expands a class as written by `alphabet_regex`.
+        fn class_members(class: &str) -> Vec<u8> {
+            let mut bytes = class.strip_prefix('[').unwrap().strip_suffix(']').unwrap().bytes();
+            let mut ret = Vec::new();
+            while let Some(c) = bytes.next() {
+                match c {
+                    b'\\' => ret.push(bytes.next().unwrap()),
+                    b'-' => {
+                        let start = *ret.last().unwrap();
+                        let end = match bytes.next().unwrap() {
+                            b'\\' => bytes.next().unwrap(),
+                            end => end,
+                        };
+                        ret.extend(start + 1..=end);
+                    }
+                    c => ret.push(c),
+                }
+            }
+            ret
+        }
+
+        static PUNCTUATION: CustomAlphabet =
+            base32_alphabet!("&'()*+,-./0123456789:;<>?@[\\]^_`");
+        let alphabets = [
+            Crockford,
+            Rfc4648 { padding: true },
+            Rfc4648Lower { padding: false },
+            #[cfg(feature = "hex")]
+            Rfc4648Hex { padding: true },
+            #[cfg(feature = "hex")]
+            Rfc4648HexLower { padding: false },
+            #[cfg(feature = "zbase32")]
+            Z,
+            #[cfg(feature = "bech32")]
+            Bech32,
+            Custom(&PUNCTUATION),
+        ];
+        for &alphabet in &alphabets {
+            let valid = (0..=255u8)
+                .filter(|&c| value(alphabet, c).is_some())
+                .collect::<Vec<u8>>();
+            assert_eq!(class_members(&alphabet_regex(alphabet)), valid);
+        }
+        #[cfg(feature = "hex")]
+        assert_eq!(alphabet_regex(Rfc4648Hex { padding: false }), "[0-9A-V]");
+        #[cfg(feature = "zbase32")]
+        assert_eq!(alphabet_regex(Z), "[13-9a-km-uw-z]");
+        assert_eq!(alphabet_regex(Custom(&PUNCTUATION)), "[&-<>-@\\[-`]");
+    }
+
+    #[test]
+    fn encode_chunks_matches_encode() {
+        fn test(chunks: Vec<Vec<u8>>, padding: bool) -> bool {
+            let alphabet = Rfc4648 { padding };
+            let joined = chunks.concat();
+            encode_chunks(alphabet, chunks.iter().map(Vec::as_slice)) == encode(alphabet, &joined)
+        }
+        quickcheck::quickcheck(test as fn(Vec<Vec<u8>>, bool) -> bool);
+
+        let data = b"foobarbaz";
+        let chunks: [&[u8]; 5] = [&data[..1], &data[1..5], &[], &data[5..7], &data[7..]];
+        assert_eq!(encode_chunks(Crockford, chunks), encode(Crockford, data));
+        assert_eq!(encode_chunks(Crockford, []), "");
+    }
+
+    #[test]
+    fn hash32_vectors() {
+        // SHA-256 of "abc".
+        const HASH: [u8; 32] = [
+            0xBA, 0x78, 0x16, 0xBF, 0x8F, 0x01, 0xCF, 0xEA, 0x41, 0x41, 0x40, 0xDE, 0x5D, 0xAE,
+            0x22, 0x23, 0xB0, 0x03, 0x61, 0xA3, 0x96, 0x17, 0x7A, 0x9C, 0xB4, 0x10, 0xFF, 0x61,
+            0xF2, 0x00, 0x15, 0xAD,
+        ];
+        let vectors = [
+            (
+                Rfc4648 { padding: true },
+                "XJ4BNP4PAHH6UQKBIDPF3LRCEOYAGYNDSYLXVHFUCD7WD4QACWWQ====",
+            ),
+            (Crockford, "Q9W1DFWF077YMGA183F5VBH24ER06RD3JRBQN75M23ZP3WG02PPG"),
+        ];
+        for &(alphabet, encoded) in &vectors {
+            assert_eq!(encode_hash32(alphabet, &HASH), encoded);
+            assert_eq!(decode_hash32(alphabet, encoded).unwrap(), HASH);
+        }
+
+        let alphabet = Rfc4648 { padding: true };
         assert_eq!(
-            decode(Rfc4648HexLower { padding: true }, "7a7h7a7h").unwrap(),
-            [0x3A, 0x8F, 0x13, 0xA8, 0xF1]
+            decode_hash32(alphabet, "XJ4BNP4PAHH6UQKBIDPF3LRCEOYAGYNDSYLXVHFUCD7WD4QACWWQ"),
+            Err(DecodeError::UnexpectedLength {
+                got: 52,
+                expected: 56
+            })
         );
+        // The right length, but the padding is in the wrong place.
         assert_eq!(
-            decode(Rfc4648HexLower { padding: true }, "o7a7o7a7").unwrap(),
-            [0xC1, 0xD4, 0x7C, 0x1D, 0x47]
+            decode_hash32(alphabet, "XJ4BNP4PAHH6UQKBIDPF3LRCEOYAGYNDSYLXVHFUCD7WD4QACW======"),
+            Err(DecodeError::UnexpectedLength {
+                got: 31,
+                expected: 32
+            })
         );
+    }
+
+    #[test]
+    fn encode_capacity_is_exact() {
+        fn test(data: Vec<u8>, padding: bool, newline: bool) -> bool {
+            let config = Config::new(Rfc4648 { padding }).trailing_newline(newline);
+            let encoded = config.encode(&data);
+            encoded.capacity() == encoded.len()
+                && encoded.len() == config.encoded_len(data.len())
+                && encoded.len() == encoded_len(Rfc4648 { padding }, data.len()) + usize::from(newline)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, bool, bool) -> bool);
+
+        let config = Config::new(Rfc4648 { padding: true });
+        assert_eq!(config.encode(b"f").capacity(), 8);
+        assert_eq!(config.encode(b"fooba").capacity(), 8);
+        assert_eq!(config.padding(false).encode(b"f").capacity(), 2);
+        assert_eq!(encode(Crockford, b"").capacity(), 0);
+        // A capacity hint only ever adds room.
+        let encoded = config.encode_with_capacity(b"foobar", 40);
+        assert_eq!(encoded, config.encode(b"foobar"));
+        assert!(encoded.capacity() >= 40);
+        assert_eq!(config.encode_with_capacity(b"foobar", 1).capacity(), 16);
+    }
+
+    #[test]
+    fn encode_array_matches_encode() {
+        fn check<const N: usize>(data: [u8; N]) {
+            let alphabets = [
+                Crockford,
+                Rfc4648 { padding: true },
+                #[cfg(feature = "zbase32")]
+                Z,
+            ];
+            for &alphabet in &alphabets {
+                let encoded = encode_array(alphabet, &data);
+                assert_eq!(encoded, encode(alphabet, &data));
+                assert_eq!(encoded.capacity(), encoded.len());
+            }
+        }
+        check([]);
+        check([0xA5]);
+        check([0xA5; 5]);
+        check([0xA5; 16]);
+        check([0xA5; 32]);
+    }
+
+    #[test]
+    fn spread_and_pack_bits() {
+        fn test(data: Vec<u8>) -> bool {
+            let mut input = [0u8; 5];
+            let n = data.len().min(5);
+            input[..n].copy_from_slice(&data[..n]);
+            let values = spread_bits(input);
+            values.iter().all(|&value| value < 32) && pack_bits(values) == input
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+
+        assert_eq!(spread_bits([0; 5]), [0; 8]);
+        assert_eq!(spread_bits([0xFF; 5]), [31; 8]);
         assert_eq!(
-            encode(Rfc4648HexLower { padding: true }, &[0xF8, 0x3E, 0x7F, 0x83]),
-            "v0v7v0o="
+            spread_bits([0x80, 0, 0, 0, 0x01]),
+            [16, 0, 0, 0, 0, 0, 0, 1]
         );
+        assert_eq!(pack_bits([16, 0, 0, 0, 0, 0, 0, 1]), [0x80, 0, 0, 0, 0x01]);
     }
 
     #[test]
-    fn masks_z() {
+    fn decode_small_spills_at_capacity() {
+        let config = Config::new(Rfc4648 { padding: true });
+        let inline = config.decode_small::<5>("MZXW6YTB").unwrap();
+        assert!(!inline.spilled());
+        assert_eq!(&*inline, b"fooba");
+        let spilled = config.decode_small::<5>("MZXW6YTBOI======").unwrap();
+        assert!(spilled.spilled());
+        assert_eq!(&*spilled, b"foobar");
+        let inline = config.decode_small::<6>("MZXW6YTBOI======").unwrap();
+        assert!(!inline.spilled());
+        assert_eq!(&*inline, b"foobar");
+
+        let spaced = config.ignore_whitespace(true);
+        let inline = spaced.decode_small::<6>("MZXW 6YTB OI== ====").unwrap();
+        assert!(!inline.spilled());
+        assert_eq!(&*inline, b"foobar");
+
         assert_eq!(
-            encode(Z, &[0xF8, 0x3E, 0x0F, 0x83, 0xE0]),
-            "9y9y9y9y"
+            config.decode_small::<8>("MZXW6YT1").unwrap_err(),
+            DecodeError::InvalidDigit {
+                index: 7,
+                byte: b'1'
+            }
         );
+        assert!(config.decode_small::<0>("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn decode_small_matches_decode() {
+        fn test(data: Vec<u8>) -> bool {
+            let encoded = encode(Crockford, &data);
+            let config = Config::new(Crockford);
+            let small = config.decode_small::<16>(&encoded).unwrap();
+            small.spilled() == (data.len() > 16) && *small == data[..]
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool)
+    }
+
+    #[test]
+    fn spans_cover_their_bits() {
+        // Flipping every bit of character `j` must change exactly the bytes whose span
+        // contains `j`.
+        fn test(data: Vec<u8>) -> bool {
+            let alphabet = Rfc4648 { padding: false };
+            let encoded = encode(alphabet, &data);
+            let (decoded, spans) = decode_with_spans(alphabet, &encoded).unwrap();
+            decoded == data
+                && (0..encoded.len()).all(|j| {
+                    let mut flipped = encoded.clone().into_bytes();
+                    flipped[j] =
+                        symbol(alphabet, value(alphabet, flipped[j]).unwrap() ^ 31).unwrap();
+                    let flipped =
+                        decode(alphabet, core::str::from_utf8(&flipped).unwrap()).unwrap();
+                    (0..data.len()).all(|i| (flipped[i] != data[i]) == spans[i].contains(&j))
+                })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+
+        let (decoded, spans) = decode_with_spans(Crockford, "CSQPYRK1E8").unwrap();
+        assert_eq!(decoded, b"foobar");
+        assert_eq!(spans, [0..2, 1..4, 3..5, 4..7, 6..8, 8..10]);
         assert_eq!(
-            encode(Z, &[0x07, 0xC1, 0xF0, 0x7C, 0x1F]),
-            "y9y9y9y9"
+            decode_with_spans(Crockford, "CSQPU"),
+            Err(DecodeError::InvalidChar {
+                index: 4,
+                byte: b'U'
+            })
         );
+    }
+
+    #[test]
+    fn canonical_case() {
+        static LOWER: CustomAlphabet = base32_alphabet!("0123456789abcdefghijklmnopqrstuv");
+        static MIXED: CustomAlphabet = base32_alphabet!("0123456789abcdefghijklmnopqrstuV");
+        let alphabets = [
+            (Crockford, Case::Upper),
+            (Rfc4648 { padding: true }, Case::Upper),
+            (Rfc4648Lower { padding: true }, Case::Lower),
+            #[cfg(feature = "hex")]
+            (Rfc4648Hex { padding: false }, Case::Upper),
+            #[cfg(feature = "hex")]
+            (Rfc4648HexLower { padding: false }, Case::Lower),
+            #[cfg(feature = "zbase32")]
+            (Z, Case::Lower),
+            (Custom(&LOWER), Case::Lower),
+            (Custom(&MIXED), Case::Upper),
+        ];
+        let data = (0..=255).collect::<Vec<u8>>();
+        for &(alphabet, case) in &alphabets {
+            assert_eq!(alphabet.canonical_case(), case);
+            if let Custom(_) = alphabet {
+                continue;
+            }
+            let encoded = encode(alphabet, &data);
+            match case {
+                Case::Upper => assert!(!encoded.bytes().any(|c| c.is_ascii_lowercase())),
+                Case::Lower => assert!(!encoded.bytes().any(|c| c.is_ascii_uppercase())),
+            }
+        }
+    }
+
+    #[test]
+    fn alphabet_metadata() {
+        let alphabets = [
+            (Crockford, Case::Upper, false),
+            (Rfc4648 { padding: true }, Case::Upper, true),
+            (Rfc4648 { padding: false }, Case::Upper, false),
+            (Rfc4648Lower { padding: true }, Case::Lower, true),
+            (Rfc4648Lower { padding: false }, Case::Lower, false),
+            #[cfg(feature = "hex")]
+            (Rfc4648Hex { padding: true }, Case::Upper, true),
+            #[cfg(feature = "hex")]
+            (Rfc4648Hex { padding: false }, Case::Upper, false),
+            #[cfg(feature = "hex")]
+            (Rfc4648HexLower { padding: true }, Case::Lower, true),
+            #[cfg(feature = "hex")]
+            (Rfc4648HexLower { padding: false }, Case::Lower, false),
+            #[cfg(feature = "zbase32")]
+            (Z, Case::Lower, false),
+            #[cfg(feature = "bech32")]
+            (Bech32, Case::Lower, false),
+        ];
+        for &(alphabet, case, padded) in &alphabets {
+            assert_eq!(alphabet.bits_per_char(), 5);
+            assert_eq!(alphabet.alphabet_size(), 32);
+            assert_eq!(alphabet.canonical_case(), case);
+            assert_eq!(alphabet.is_padded(), padded);
+            assert_eq!(alphabet.pad_char(), if padded { Some(b'=') } else { None });
+            assert_eq!(encode(alphabet, b"f").ends_with('='), padded);
+        }
+    }
+
+    #[test]
+    fn chunks_decodable_concatenate() {
+        fn test(data: Vec<u8>, block: usize) -> bool {
+            let block = (block % 4 + 1) * 8;
+            [Rfc4648 { padding: true }, Crockford]
+                .iter()
+                .all(|&alphabet| {
+                    let encoded = encode(alphabet, &data);
+                    let mut decoded = Vec::new();
+                    for chunk in chunks_decodable(&encoded, block) {
+                        decoded.extend(decode(alphabet, chunk).unwrap());
+                    }
+                    decoded == data
+                })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize) -> bool);
+
+        assert_eq!(chunks_decodable("", 8).count(), 0);
         assert_eq!(
-            decode(Z, "9y9y9y9y").unwrap(),
-            [0xF8, 0x3E, 0x0F, 0x83, 0xE0]
+            chunks_decodable("MZXW6YTBMZ\u{e9}W6YTB", 8).collect::<Vec<_>>(),
+            ["MZXW6YTB", "MZ\u{e9}W6YT", "B"]
         );
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of 8")]
+    fn chunks_decodable_unaligned() {
+        let _ = chunks_decodable("MZXW6YTB", 5);
+    }
+
+    #[test]
+    fn decode_with_fallback_maps_unknown() {
+        let alphabet = Rfc4648 { padding: true };
+        // '=' is padding and never reaches the fallback.
+        let decoded = decode_with_fallback(alphabet, "MZXW6YTBOI======", |_| unreachable!());
+        assert_eq!(decoded.unwrap(), b"foobar");
+
+        let lower = |c: u8| value(alphabet, c.to_ascii_uppercase());
+        let decoded = decode_with_fallback(alphabet, "mzxw6ytboi======", lower);
+        assert_eq!(decoded.unwrap(), b"foobar");
+
         assert_eq!(
-            decode(Z, "y9y9y9y9").unwrap(),
-            [0x07, 0xC1, 0xF0, 0x7C, 0x1F]
+            decode_with_fallback(alphabet, "MZXW6YTBO!======", |_| Some(32)),
+            Err(DecodeError::InvalidChar {
+                index: 9,
+                byte: b'!'
+            })
         );
     }
 
+    #[cfg(feature = "zbase32")]
     #[test]
-    fn padding() {
-        let num_padding = [0, 6, 4, 3, 1];
-        for i in 1..6 {
-            let encoded = encode(
-                Rfc4648 { padding: true },
-                (0..(i as u8)).collect::<Vec<u8>>().as_ref(),
+    fn z_vectors() {
+        assert_eq!(encode(Z, &[0x00]), "yy");
+        assert_eq!(encode(Z, &[0x80]), "oy");
+        assert_eq!(encode(Z, &[0xF0, 0xBF, 0xC7]), "6n9hq");
+        assert_eq!(encode(Z, &[0xD4, 0x7A, 0x04]), "4t7ye");
+        assert_eq!(decode(Z, "6n9hq").unwrap(), [0xF0, 0xBF, 0xC7]);
+        assert_eq!(decode(Z, "4t7ye").unwrap(), [0xD4, 0x7A, 0x04]);
+    }
+
+    #[cfg(feature = "zbase32")]
+    #[test]
+    fn z_invalid_length() {
+        for &length in &[1, 3, 6, 9, 11, 14] {
+            let data = "y".repeat(length);
+            assert_eq!(
+                Config::new(Z).decode(&data),
+                Err(DecodeError::InvalidLength { length })
             );
-            assert_eq!(encoded.len(), 8);
-            for j in 0..(num_padding[i % 5]) {
-                assert_eq!(encoded.as_bytes()[encoded.len() - j - 1], b'=');
+            assert_eq!(
+                Config::new(Z).decode_chars(data.chars()),
+                Err(DecodeError::InvalidLength { length })
+            );
+        }
+        for &length in &[0, 2, 4, 5, 7, 8, 10] {
+            let data = "y".repeat(length);
+            assert_eq!(decode(Z, &data).unwrap(), &[0; 8][..length * 5 / 8]);
+        }
+    }
+
+    #[test]
+    fn unpadded_rfc4648_invalid_length() {
+        let alphabets = [
+            Rfc4648 { padding: false },
+            Rfc4648Lower { padding: false },
+            #[cfg(feature = "hex")]
+            Rfc4648Hex { padding: false },
+            #[cfg(feature = "hex")]
+            Rfc4648HexLower { padding: false },
+        ];
+        for &alphabet in &alphabets {
+            for &length in &[1, 3, 6, 9, 11, 14] {
+                let data = "A".repeat(length);
+                let config = Config::new(alphabet).case_insensitive(true);
+                assert_eq!(
+                    config.decode(&data),
+                    Err(DecodeError::InvalidLength { length })
+                );
+                assert_eq!(
+                    config.decode_chars(data.chars()),
+                    Err(DecodeError::InvalidLength { length })
+                );
             }
-            for j in 0..(8 - num_padding[i % 5]) {
-                assert!(encoded.as_bytes()[j] != b'=');
+            for &length in &[0, 2, 4, 5, 7, 8, 10] {
+                let data = encode(alphabet, &[0; 8][..length * 5 / 8]);
+                assert_eq!(data.len(), length);
+                assert_eq!(decode(alphabet, &data).unwrap(), &[0; 8][..length * 5 / 8]);
             }
         }
+        // Padded input with its padding stripped is still accepted.
+        assert_eq!(decode(Rfc4648 { padding: true }, "MZX").unwrap(), b"f");
+        assert_eq!(decode(Crockford, "ZZZ").unwrap(), [0xFF]);
+    }
+
+    #[cfg(feature = "zbase32")]
+    #[test]
+    fn invertible_z() {
+        fn test(data: Vec<u8>) -> bool {
+            decode(Z, encode(Z, data.as_ref()).as_ref()).unwrap() == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool)
+    }
+
+    #[test]
+    fn symbol_value() {
+        let alphabets = [
+            Crockford,
+            Rfc4648 { padding: true },
+            Rfc4648Lower { padding: false },
+            #[cfg(feature = "hex")]
+            Rfc4648Hex { padding: true },
+            #[cfg(feature = "hex")]
+            Rfc4648HexLower { padding: false },
+            #[cfg(feature = "zbase32")]
+            Z,
+        ];
+        for &alphabet in &alphabets {
+            let encoded = encode(
+                alphabet,
+                &[
+                    0x00, 0x44, 0x32, 0x14, 0xC7, 0x42, 0x54, 0xB6, 0x35, 0xCF, 0x84, 0x65, 0x3A,
+                    0x56, 0xD7, 0xC6, 0x75, 0xBE, 0x77, 0xDF,
+                ],
+            );
+            for (v, &c) in encoded.as_bytes().iter().enumerate() {
+                assert_eq!(symbol(alphabet, v as u8), Some(c));
+                assert_eq!(value(alphabet, c), Some(v as u8));
+            }
+            assert_eq!(symbol(alphabet, 32), None);
+            assert_eq!(symbol(alphabet, 255), None);
+            assert_eq!(value(alphabet, b'='), None);
+            assert_eq!(value(alphabet, 0xFF), None);
+        }
+        assert_eq!(value(Crockford, b'o'), Some(0));
+        assert_eq!(value(Rfc4648 { padding: true }, b'a'), None);
+    }
+
+    #[test]
+    fn invertible_padded_crockford() {
+        fn test(data: Vec<u8>) -> bool {
+            let config = Config::new(Crockford).padding(true);
+            config
+                .decode(config.encode(data.as_ref()).as_ref())
+                .unwrap()
+                == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool)
     }
 
     #[test]
@@ -509,6 +5097,72 @@ mod test {
         assert_eq!(decode(Crockford, "IiLlOo"), decode(Crockford, "111100"));
     }
 
+    #[test]
+    fn crockford_folding_exhaustive() {
+        const ONES: &[u8] = b"iIlL1";
+        const ZEROS: &[u8] = b"oO0";
+        let symbols = [ONES, ZEROS].concat();
+        // Every 4-character string over the ambiguous symbols, wrapped in unambiguous ones.
+        for n in 0..symbols.len().pow(4) {
+            let mut input = Vec::from(&b"ZZ"[..]);
+            let mut canonical = input.clone();
+            let mut rest = n;
+            for _ in 0..4 {
+                let c = symbols[rest % symbols.len()];
+                rest /= symbols.len();
+                input.push(c);
+                canonical.push(if ONES.contains(&c) { b'1' } else { b'0' });
+            }
+            input.extend_from_slice(b"ZZ");
+            canonical.extend_from_slice(b"ZZ");
+            let input = String::from_utf8(input).unwrap();
+            let canonical = String::from_utf8(canonical).unwrap();
+            assert_eq!(
+                decode(Crockford, &input).unwrap(),
+                decode(Crockford, &canonical).unwrap(),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_newline() {
+        for &(data, base32, _) in RFC4648_VECTORS {
+            let line = encode_line(Rfc4648 { padding: true }, data);
+            assert_eq!(line.strip_suffix('\n'), Some(base32));
+            let config = Config::new(Rfc4648 { padding: true }).trailing_newline(true);
+            assert_eq!(config.encoded_len(data.len()), line.len());
+            assert_eq!(config.decode(&line).unwrap(), data);
+            assert_eq!(config.decode(base32).unwrap(), data);
+        }
+        let config = Config::new(Crockford).trailing_newline(true);
+        assert_eq!(config.encode(&[]), "\n");
+        assert_eq!(config.decode("\n").unwrap(), []);
+        // Only a single newline is stripped.
+        assert!(config.decode("Z0\n\n").is_err());
+        assert_eq!(decode(Crockford, "Z0\n"), None);
+
+        // The canonical form is what `encode` writes, newline included.
+        let config = Config::new(Rfc4648 { padding: true }).trailing_newline(true);
+        assert_eq!(config.encode(b"foo"), "MZXW6===\n");
+        assert!(matches!(
+            config.normalize("MZXW6===\n"),
+            Ok(Cow::Borrowed("MZXW6===\n"))
+        ));
+        for data in ["MZXW6===", "mzxw6===\n", "MZXW6"] {
+            let normalized = config.case_insensitive(true).normalize(data).unwrap();
+            assert!(matches!(normalized, Cow::Owned(_)));
+            assert_eq!(normalized, "MZXW6===\n");
+        }
+        let (bytes, canonical) = config.decode_and_canonicalize("MZXW6===").unwrap();
+        assert_eq!(bytes, b"foo");
+        assert_eq!(canonical, "MZXW6===\n");
+        assert_eq!(config.decode_and_canonicalize("MZXW6===\n").unwrap().1, "MZXW6===\n");
+        assert_eq!(config.normalize("\n").unwrap(), "\n");
+        assert_eq!(config.normalize("").unwrap(), "\n");
+    }
+
     #[test]
     fn invalid_chars_crockford() {
         assert_eq!(decode(Crockford, ","), None)
@@ -525,6 +5179,6 @@ mod test {
     }
 }
 
-#[cfg(doctest)]
+#[cfg(all(doctest, feature = "hex", feature = "zbase32"))]
 #[doc = include_str!("../README.md")]
 struct Readme;
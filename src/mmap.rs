@@ -0,0 +1,64 @@
+//! Encoding files through a memory map.
+
+use alloc::string::String;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use super::{encode, Alphabet};
+
+/// Encodes the contents of the file at `path` like [`encode`](super::encode), reading it
+/// through a memory map rather than into a `Vec` first, so a large file is not held in memory
+/// twice. Requires the `memmap2` feature.
+///
+/// The file must not be modified or truncated by this or any other process while it is being
+/// encoded. A concurrent write can make the output a mix of old and new contents, and
+/// truncation can kill the process with `SIGBUS`. Use [`encode_from_reader`] for files that
+/// may change.
+///
+/// [`encode_from_reader`]: super::encode_from_reader
+///
+/// ```no_run
+/// use base32::{encode_mmap, Alphabet};
+///
+/// let encoded = encode_mmap(Alphabet::Crockford, "image.iso").unwrap();
+/// ```
+pub fn encode_mmap<P: AsRef<Path>>(alphabet: Alphabet, path: P) -> io::Result<String> {
+    let file = File::open(path)?;
+    // SAFETY: `Mmap::map` is unsafe because the bytes behind the `&[u8]` it derefs to change if
+    // the file is modified while mapped. The map never leaves this call, and the documentation
+    // above requires the file to stay unchanged until it returns. The encoder reads each byte
+    // once and relies on nothing about their values, so no check here can be invalidated.
+    #[allow(unsafe_code)]
+    let map = unsafe { Mmap::map(&file)? };
+    Ok(encode(alphabet, &map))
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::encode;
+    use super::super::Alphabet::{Crockford, Rfc4648};
+    use super::encode_mmap;
+    use alloc::format;
+    use alloc::vec::Vec;
+    use std::fs;
+    use std::io::ErrorKind;
+    use std::process;
+
+    #[test]
+    fn temp_file() {
+        let path = std::env::temp_dir().join(format!("base32-mmap-{}", process::id()));
+        let data = (0..100_003).map(|i| (i * 7) as u8).collect::<Vec<u8>>();
+        for len in [0, 1, 5, data.len()] {
+            fs::write(&path, &data[..len]).unwrap();
+            let alphabet = Rfc4648 { padding: true };
+            assert_eq!(encode_mmap(alphabet, &path).unwrap(), encode(alphabet, &data[..len]));
+        }
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            encode_mmap(Crockford, &path).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! The base32 used by Nix for store paths and hashes.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use super::{ascii_string, decoded_len, unpadded_len, DecodeError};
+
+/// Nix's alphabet: the digits and lowercase letters without `e`, `o`, `t` and `u`.
+const NIX: super::CustomAlphabet = base32_alphabet!("0123456789abcdfghijklmnpqrsvwxyz");
+
+/// Encodes `data` the way Nix prints hashes and store path digests. Requires the `nix` feature.
+///
+/// Nix reads the bytes as one little-endian number and writes it out most significant symbol
+/// first, so the first byte ends up in the last symbols. This is neither RFC4648 packing nor
+/// [`BitOrder::LsbFirst`](super::BitOrder::LsbFirst), which would be the same symbols in the
+/// opposite order. The output has `ceil(8 * data.len() / 5)` symbols and no padding.
+///
+/// ```
+/// use base32::encode_nix;
+///
+/// assert_eq!(encode_nix(&[0x01]), "01");
+/// assert_eq!(encode_nix(&[0xFF, 0x00]), "007z");
+/// ```
+pub fn encode_nix(data: &[u8]) -> String {
+    let symbols = NIX.symbols();
+    let len = unpadded_len(data.len());
+    let ret = (0..len)
+        .rev()
+        .map(|n| {
+            let (i, j) = position(n);
+            let low = (data[i] >> j) as u16;
+            let high = data.get(i + 1).map_or(0, |&b| (b as u16) << (8 - j));
+            symbols[((low | high) & 0x1F) as usize]
+        })
+        .collect::<Vec<u8>>();
+    ascii_string(ret)
+}
+
+/// The byte and bit within it where symbol `n`, counting from the end, starts: bit `5 * n`,
+/// computed without overflowing.
+fn position(n: usize) -> (usize, usize) {
+    (n / 8 * 5 + n % 8 * 5 / 8, n % 8 * 5 % 8)
+}
+
+/// Decodes the output of [`encode_nix`]. Requires the `nix` feature.
+///
+/// Like Nix, decoding is case-sensitive, and the first symbol must not carry bits past the
+/// end of the last byte; such a symbol is reported as [`DecodeError::InvalidChar`]. Lengths
+/// no input encodes to give [`DecodeError::InvalidLength`].
+///
+/// ```
+/// use base32::{decode_nix, DecodeError};
+///
+/// assert_eq!(decode_nix("007z").unwrap(), [0xFF, 0x00]);
+/// assert_eq!(decode_nix("407z"), Err(DecodeError::InvalidChar { index: 0, byte: b'4' }));
+/// ```
+pub fn decode_nix(data: &str) -> Result<Vec<u8>, DecodeError> {
+    let data = data.as_bytes();
+    let len = data.len();
+    if matches!(len % 8, 1 | 3 | 6) {
+        return Err(DecodeError::InvalidLength { length: len });
+    }
+    let values = NIX.values();
+    let mut ret = vec![0u8; decoded_len(len)];
+    // Symbol `index` holds bits `5 * n` to `5 * n + 4` of the number, counting from the end.
+    for (index, &c) in data.iter().enumerate() {
+        let value = match values[c as usize] {
+            -1 if !c.is_ascii() => {
+                let start = (0..=index).rev().find(|&i| data[i] & 0xC0 != 0x80);
+                return Err(DecodeError::NonAscii {
+                    index: start.unwrap_or(index),
+                });
+            }
+            -1 => return Err(DecodeError::InvalidChar { index, byte: c }),
+            value => value as u16,
+        };
+        let n = len - 1 - index;
+        let (i, j) = position(n);
+        ret[i] |= (value << j) as u8;
+        let high = (value >> (8 - j)) as u8;
+        match ret.get_mut(i + 1) {
+            Some(b) => *b |= high,
+            None if high != 0 => return Err(DecodeError::InvalidChar { index, byte: c }),
+            None => {}
+        }
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::DecodeError;
+    use super::{decode_nix, encode_nix, position};
+    use alloc::vec::Vec;
+
+    // SHA-256 and SHA-1 of "abc" and MD5 of "", with their Nix base32 forms from Nix's own tests.
+    const VECTORS: [(&str, &str); 3] = [
+        (
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            "1b8m03r63zqhnjf7l5wnldhh7c134ap5vpj0850ymkq1iyzicy5s",
+        ),
+        (
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+            "kpcd173cq987hw957sx6m0868wv3x6d9",
+        ),
+        (
+            "d41d8cd98f00b204e9800998ecf8427e",
+            "3y8bwfr609h3lh9ch0izcqq7fl",
+        ),
+    ];
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn known_hashes() {
+        for &(hex, nix) in &VECTORS {
+            let digest = from_hex(hex);
+            assert_eq!(encode_nix(&digest), nix);
+            assert_eq!(decode_nix(nix).unwrap(), digest);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        fn test(data: Vec<u8>) -> bool {
+            decode_nix(&encode_nix(&data)).unwrap() == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn positions() {
+        for n in 0..100 {
+            assert_eq!(position(n), (n * 5 / 8, n * 5 % 8));
+        }
+        assert_eq!(position(usize::MAX), (usize::MAX / 8 * 5 + 4, 3));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(decode_nix("").unwrap(), []);
+        assert_eq!(
+            decode_nix("0"),
+            Err(DecodeError::InvalidLength { length: 1 })
+        );
+        assert_eq!(
+            decode_nix("0e"),
+            Err(DecodeError::InvalidChar {
+                index: 1,
+                byte: b'e'
+            })
+        );
+        assert_eq!(
+            decode_nix("0A"),
+            Err(DecodeError::InvalidChar {
+                index: 1,
+                byte: b'A'
+            })
+        );
+        assert_eq!(decode_nix("7z").unwrap(), [0xFF]);
+        assert_eq!(
+            decode_nix("8z"),
+            Err(DecodeError::InvalidChar {
+                index: 0,
+                byte: b'8'
+            })
+        );
+        assert_eq!(decode_nix("0é0"), Err(DecodeError::NonAscii { index: 1 }));
+    }
+}
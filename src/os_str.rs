@@ -0,0 +1,69 @@
+use alloc::vec::Vec;
+use std::ffi::OsStr;
+
+use super::{Alphabet, Config, DecodeError};
+
+/// Decodes a filename or other OS string, such as the name of a file in a content-addressed
+/// store, without converting it to UTF-8 first. Requires the `std` feature.
+///
+/// The raw bytes of `s` are decoded: on Unix exactly the bytes of the filename, elsewhere the
+/// platform's encoding of it, which agrees with UTF-8 on the ASCII symbols. A name that is not
+/// valid UTF-8 is therefore not an error in itself; its first non-ASCII byte is reported as
+/// [`DecodeError::NonAscii`] at its byte index, like any other non-symbol.
+///
+/// ```
+/// use std::path::Path;
+/// use base32::{decode_os_str, Alphabet};
+///
+/// let path = Path::new("/var/store/MZXW6YTBOI");
+/// let name = path.file_name().unwrap();
+/// assert_eq!(decode_os_str(Alphabet::Rfc4648 { padding: false }, name).unwrap(), b"foobar");
+/// ```
+pub fn decode_os_str(alphabet: Alphabet, s: &OsStr) -> Result<Vec<u8>, DecodeError> {
+    Config::new(alphabet)
+        .decode_bytes(s.as_encoded_bytes())
+        .map(|decoded| decoded.data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{encode, Alphabet, DecodeError};
+    use super::decode_os_str;
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    #[test]
+    fn file_names() {
+        let alphabet = Alphabet::Rfc4648Lower { padding: false };
+        let digest = [0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x01];
+        let path = Path::new("objects").join(encode(alphabet, &digest));
+        let name = path.file_name().unwrap();
+        assert_eq!(decode_os_str(alphabet, name).unwrap(), digest);
+
+        let name = Path::new("objects/3ww").file_name().unwrap();
+        assert_eq!(
+            decode_os_str(alphabet, name),
+            Err(DecodeError::InvalidLength { length: 3 })
+        );
+        assert_eq!(
+            decode_os_str(alphabet, OsStr::new("mzxé")),
+            Err(DecodeError::NonAscii { index: 3 })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let alphabet = Alphabet::Rfc4648 { padding: false };
+        assert_eq!(
+            decode_os_str(alphabet, OsStr::from_bytes(b"MZXW6YTBOI")).unwrap(),
+            b"foobar"
+        );
+        assert_eq!(
+            decode_os_str(alphabet, OsStr::from_bytes(b"MZ\xFFW")),
+            Err(DecodeError::NonAscii { index: 2 })
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroUsize;
+use std::thread;
+
+use super::{
+    ascii_string, encode_chunk, validate_batch, Alphabet, Config, DecodeError, INPUT_CHUNK,
+    OUTPUT_CHUNK,
+};
+
+/// Inputs shorter than this are encoded on the calling thread; below it spawning threads
+/// costs more than it saves.
+const MIN_PARALLEL: usize = 1024 * 1024;
+
+/// Encodes `data` like [`encode`](super::encode), splitting it into 5-byte aligned segments
+/// that are encoded on one thread per available core. The unaligned tail, the only part that
+/// can be padded, is encoded on the calling thread. The output is identical to `encode`.
+///
+/// Requires the `parallel` feature. The segments run on [`std::thread::scope`] rather than a
+/// rayon thread pool, so the feature adds no dependency; each call spawns its own threads.
+///
+/// ```
+/// use base32::{encode, encode_par, Alphabet};
+///
+/// let alphabet = Alphabet::Rfc4648 { padding: true };
+/// let data = vec![0xA5; 3 * 1024 * 1024 + 3];
+/// assert_eq!(encode_par(alphabet, &data), encode(alphabet, &data));
+/// ```
+pub fn encode_par(alphabet: Alphabet, data: &[u8]) -> String {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    encode_threads(Config::new(alphabet), data, threads, MIN_PARALLEL)
+}
+
+fn encode_threads(config: Config, data: &[u8], threads: usize, min_parallel: usize) -> String {
+    if threads < 2 || data.len() < min_parallel {
+        return config.encode(data);
+    }
+
+    let (body, tail) = data.split_at(data.len() / INPUT_CHUNK * INPUT_CHUNK);
+    let mut ret = vec![0; config.encoded_len(data.len())];
+    let (out_body, out_tail) = ret.split_at_mut(body.len() / INPUT_CHUNK * OUTPUT_CHUNK);
+
+    let groups = (body.len() / INPUT_CHUNK).div_ceil(threads).max(1);
+    let symbols = config.alphabet.table().symbols();
+    let bit_order = config.bit_order;
+    thread::scope(|scope| {
+        let segments = body.chunks(groups * INPUT_CHUNK);
+        for (input, output) in segments.zip(out_body.chunks_mut(groups * OUTPUT_CHUNK)) {
+            scope.spawn(move || {
+                let groups = input.chunks_exact(INPUT_CHUNK);
+                for (chunk, out) in groups.zip(output.chunks_exact_mut(OUTPUT_CHUNK)) {
+                    out.copy_from_slice(&encode_chunk(symbols, chunk, bit_order));
+                }
+            });
+        }
+    });
+
+    let mut encoded_tail = vec![];
+    config.encode_into(tail, &mut encoded_tail);
+    out_tail.copy_from_slice(&encoded_tail);
+
+    ascii_string(ret)
+}
+
+/// Checks `items` like [`validate_batch`], splitting the batch into runs of consecutive items
+/// that are checked on one thread per available core. The results are in input order and
+/// identical to `validate_batch`. Batches of less than 1 MiB in total are checked on the
+/// calling thread.
+///
+/// Requires the `parallel` feature.
+///
+/// ```
+/// use base32::{validate_batch, validate_batch_par, Alphabet};
+///
+/// let items = ["CSQPY"; 1000];
+/// assert_eq!(
+///     validate_batch_par(Alphabet::Crockford, &items),
+///     validate_batch(Alphabet::Crockford, &items)
+/// );
+/// ```
+pub fn validate_batch_par(alphabet: Alphabet, items: &[&str]) -> Vec<Result<(), DecodeError>> {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    validate_threads(alphabet, items, threads, MIN_PARALLEL)
+}
+
+fn validate_threads(
+    alphabet: Alphabet,
+    items: &[&str],
+    threads: usize,
+    min_parallel: usize,
+) -> Vec<Result<(), DecodeError>> {
+    let total = items.iter().map(|item| item.len()).sum::<usize>();
+    if threads < 2 || items.len() < 2 || total < min_parallel {
+        return validate_batch(alphabet, items);
+    }
+
+    let run = items.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let handles = items
+            .chunks(run)
+            .map(|run| scope.spawn(move || validate_batch(alphabet, run)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{encode, Alphabet};
+    use super::{encode_threads, validate_batch, validate_threads, Config};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn matches_serial() {
+        fn test(data: Vec<u8>, threads: u8, padding: bool) -> bool {
+            let alphabet = Alphabet::Rfc4648 { padding };
+            let threads = usize::from(threads % 8) + 2;
+            encode_threads(Config::new(alphabet), &data, threads, 0) == encode(alphabet, &data)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, u8, bool) -> bool);
+    }
+
+    #[test]
+    fn validate_matches_serial() {
+        fn test(items: Vec<String>, threads: u8) -> bool {
+            let alphabet = Alphabet::Rfc4648 { padding: true };
+            let items = items.iter().map(String::as_str).collect::<Vec<_>>();
+            let threads = usize::from(threads % 8) + 2;
+            validate_threads(alphabet, &items, threads, 0) == validate_batch(alphabet, &items)
+        }
+        quickcheck::quickcheck(test as fn(Vec<String>, u8) -> bool);
+    }
+}
@@ -0,0 +1,325 @@
+//! Encoding in any power-of-two base up to 64 with the same bit packing as base32.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{ascii_string, DecodeError};
+
+/// An alphabet of `2^bits` symbols for encoding in base 2, 4, 8, 16, 32 or 64.
+///
+/// The bytes are read as one stream of bits, most significant first, and every `bits` of it
+/// become a symbol, exactly as RFC4648 describes for base16, base32 and base64. The last symbol
+/// is filled up with zero bits. There is no padding, so `n` bytes always take
+/// `ceil(8 * n / bits)` symbols.
+///
+/// A group of `bits` bytes is always exactly 8 symbols, so every base is encoded a group at a
+/// time with the same bit packing as [`encode`](super::encode) and [`decode`](super::decode),
+/// which are this code specialized to 5 bits, plus padding, aliases and the other [`Config`]
+/// options.
+///
+/// [`Config`]: super::Config
+///
+/// ```
+/// use base32::Radix;
+///
+/// assert_eq!(Radix::BASE16.encode(b"foo"), "666F6F");
+/// assert_eq!(Radix::BASE32.encode(b"foo"), "MZXW6");
+/// assert_eq!(Radix::BASE64.encode(b"foo"), "Zm9v");
+/// assert_eq!(Radix::BASE64.decode("Zm9vYg").unwrap(), b"foob");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Radix {
+    bits: u8,
+    symbols: [u8; 64],
+    values: [i8; 256],
+}
+
+impl Radix {
+    /// RFC4648 base16, i.e. uppercase hexadecimal.
+    pub const BASE16: Radix = Radix::new(4, "0123456789ABCDEF");
+    /// The RFC4648 base32 alphabet, without padding.
+    pub const BASE32: Radix = Radix::new(5, "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567");
+    /// The RFC4648 base64 alphabet, without padding.
+    pub const BASE64: Radix =
+        Radix::new(6, "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/");
+
+    /// Builds the tables for `symbols`, which must be `2^bits` distinct ASCII characters other
+    /// than control characters and `=`, with `bits` from 1 to 6.
+    ///
+    /// Like [`CustomAlphabet::new`](super::CustomAlphabet::new), this is a `const fn`, so
+    /// invalid symbols in a `const` fail the build. Called at runtime, it panics instead.
+    ///
+    /// ```
+    /// use base32::Radix;
+    ///
+    /// const OCTAL: Radix = Radix::new(3, "01234567");
+    ///
+    /// assert_eq!(OCTAL.encode(&[0xFF]), "776");
+    /// ```
+    pub const fn new(bits: u8, symbols: &str) -> Radix {
+        assert!(bits >= 1 && bits <= 6, "a radix must have 1 to 6 bits per symbol");
+        let symbols = symbols.as_bytes();
+        let len = 1 << bits;
+        assert!(
+            symbols.len() == len,
+            "a radix alphabet needs exactly 2^bits symbols"
+        );
+
+        let mut ret = Radix {
+            bits,
+            symbols: [0; 64],
+            values: [-1; 256],
+        };
+        let mut i = 0;
+        while i < len {
+            let c = symbols[i];
+            assert!(c.is_ascii(), "radix symbols must be ASCII");
+            assert!(!c.is_ascii_control(), "radix symbols must not be control characters");
+            assert!(c != b'=', "`=` is reserved for padding");
+            assert!(ret.values[c as usize] == -1, "radix symbols must be unique");
+            ret.symbols[i] = c;
+            ret.values[c as usize] = i as i8;
+            i += 1;
+        }
+        ret
+    }
+
+    /// The number of bits each symbol carries.
+    pub const fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// The symbol for each value, `2^bits` of them.
+    pub fn symbols(&self) -> &[u8] {
+        &self.symbols[..1 << self.bits]
+    }
+
+    /// The value for each byte, or `-1` if the byte is not a symbol.
+    pub const fn values(&self) -> &[i8; 256] {
+        &self.values
+    }
+
+    /// Returns the number of symbols [`encode`](Radix::encode) produces for `len` bytes,
+    /// saturating at `usize::MAX` like [`Config::encoded_len`](super::Config::encoded_len).
+    pub fn encoded_len(&self, len: usize) -> usize {
+        let bits = self.bits as usize;
+        (len / bits)
+            .saturating_mul(8)
+            .saturating_add((len % bits * 8).div_ceil(bits))
+    }
+
+    /// The number of bytes `len` symbols decode to, computed without overflowing.
+    fn decoded_len(&self, len: usize) -> usize {
+        let bits = self.bits as usize;
+        len / 8 * bits + len % 8 * bits / 8
+    }
+
+    pub fn encode(&self, data: &[u8]) -> String {
+        match self.bits {
+            1 => self.encode_groups::<1>(data),
+            2 => self.encode_groups::<2>(data),
+            3 => self.encode_groups::<3>(data),
+            4 => self.encode_groups::<4>(data),
+            5 => self.encode_groups::<5>(data),
+            _ => self.encode_groups::<6>(data),
+        }
+    }
+
+    /// Encodes `data` a group of `BITS` bytes at a time, with `BITS` known at compile time so
+    /// that [`spread`] compiles to fixed shifts.
+    fn encode_groups<const BITS: u32>(&self, data: &[u8]) -> String {
+        let mut ret = Vec::with_capacity(self.encoded_len(data.len()));
+        let symbols = |group: &[u8]| {
+            let mut bytes = [0u8; 8];
+            bytes[8 - BITS as usize..][..group.len()].copy_from_slice(group);
+            spread::<BITS>(u64::from_be_bytes(bytes)).map(|value| self.symbols[value as usize])
+        };
+        let chunks = data.chunks_exact(BITS as usize);
+        let rest = chunks.remainder();
+        for chunk in chunks {
+            ret.extend_from_slice(&symbols(chunk));
+        }
+        if !rest.is_empty() {
+            ret.extend_from_slice(&symbols(rest)[..(rest.len() * 8).div_ceil(BITS as usize)]);
+        }
+        ascii_string(ret)
+    }
+
+    /// Decodes the output of [`encode`](Radix::encode). Decoding is case-sensitive, and the
+    /// unused bits of the last symbol are ignored.
+    ///
+    /// A character that is not a symbol is reported as [`DecodeError::InvalidChar`], or
+    /// [`DecodeError::NonAscii`], at its byte index in `data`. A length no input encodes to,
+    /// such as a single base64 symbol, is [`DecodeError::InvalidLength`].
+    pub fn decode(&self, data: &str) -> Result<Vec<u8>, DecodeError> {
+        let data = data.as_bytes();
+        let output_len = self.decoded_len(data.len());
+        if self.encoded_len(output_len) != data.len() {
+            return Err(DecodeError::InvalidLength { length: data.len() });
+        }
+        let mut ret = Vec::with_capacity(output_len);
+        match self.bits {
+            1 => self.decode_groups::<1>(data, &mut ret)?,
+            2 => self.decode_groups::<2>(data, &mut ret)?,
+            3 => self.decode_groups::<3>(data, &mut ret)?,
+            4 => self.decode_groups::<4>(data, &mut ret)?,
+            5 => self.decode_groups::<5>(data, &mut ret)?,
+            _ => self.decode_groups::<6>(data, &mut ret)?,
+        }
+        Ok(ret)
+    }
+
+    /// Decodes `data` 8 symbols at a time into groups of `BITS` bytes, appending them to `ret`.
+    fn decode_groups<const BITS: u32>(
+        &self,
+        data: &[u8],
+        ret: &mut Vec<u8>,
+    ) -> Result<(), DecodeError> {
+        for (chunk_index, chunk) in data.chunks(8).enumerate() {
+            let mut values = [0u8; 8];
+            for (i, (&c, value)) in chunk.iter().zip(&mut values).enumerate() {
+                let index = chunk_index * 8 + i;
+                *value = match self.values[c as usize] {
+                    -1 if !c.is_ascii() => return Err(DecodeError::NonAscii { index }),
+                    -1 => return Err(DecodeError::InvalidChar { index, byte: c }),
+                    value => value as u8,
+                };
+            }
+            let group = pack::<BITS>(values).to_be_bytes();
+            ret.extend_from_slice(&group[8 - BITS as usize..][..chunk.len() * BITS as usize / 8]);
+        }
+        Ok(())
+    }
+}
+
+/// Splits the low `8 * BITS` bits of `group` into 8 values of `BITS` bits, most significant
+/// first. This is the bit packing of every base: [`spread_bits`](super::spread_bits) is this
+/// with `BITS` = 5.
+#[inline]
+pub(crate) fn spread<const BITS: u32>(group: u64) -> [u8; 8] {
+    let mask = (1 << BITS) - 1;
+    core::array::from_fn(|i| (group >> (BITS * (7 - i as u32)) & mask) as u8)
+}
+
+/// Joins 8 values of `BITS` bits, most significant first, into the low `8 * BITS` bits of the
+/// result: the inverse of [`spread`]. Values of `BITS` or more bits spill into their
+/// neighbours.
+#[inline]
+pub(crate) fn pack<const BITS: u32>(values: [u8; 8]) -> u64 {
+    values
+        .iter()
+        .fold(0, |group, &value| group << BITS | value as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{decode, encode, Alphabet, DecodeError};
+    use super::Radix;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn rfc4648_vectors() {
+        // RFC4648 section 10, without padding.
+        let vectors = [
+            ("", "", "", ""),
+            ("f", "66", "MY", "Zg"),
+            ("fo", "666F", "MZXQ", "Zm8"),
+            ("foo", "666F6F", "MZXW6", "Zm9v"),
+            ("foob", "666F6F62", "MZXW6YQ", "Zm9vYg"),
+            ("fooba", "666F6F6261", "MZXW6YTB", "Zm9vYmE"),
+            ("foobar", "666F6F626172", "MZXW6YTBOI", "Zm9vYmFy"),
+        ];
+        for &(data, base16, base32, base64) in &vectors {
+            for &(radix, encoded) in &[
+                (Radix::BASE16, base16),
+                (Radix::BASE32, base32),
+                (Radix::BASE64, base64),
+            ] {
+                assert_eq!(radix.encode(data.as_bytes()), encoded);
+                assert_eq!(radix.decode(encoded).unwrap(), data.as_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn base32_matches_encode() {
+        fn test(data: Vec<u8>) -> bool {
+            let alphabet = Alphabet::Rfc4648 { padding: false };
+            let encoded = Radix::BASE32.encode(&data);
+            encoded == encode(alphabet, &data) && decode(alphabet, &encoded).unwrap() == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn round_trip() {
+        const BINARY: Radix = Radix::new(1, "01");
+        const OCTAL: Radix = Radix::new(3, "01234567");
+        fn test(data: Vec<u8>) -> bool {
+            [BINARY, OCTAL, Radix::BASE16, Radix::BASE32, Radix::BASE64]
+                .iter()
+                .all(|radix| radix.decode(&radix.encode(&data)).unwrap() == data)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+        assert_eq!(BINARY.encode(&[0xA5]), "10100101");
+        assert_eq!(OCTAL.decode("776").unwrap(), [0xFF]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            Radix::BASE64.decode("Z"),
+            Err(DecodeError::InvalidLength { length: 1 })
+        );
+        assert_eq!(
+            Radix::BASE16.decode("666"),
+            Err(DecodeError::InvalidLength { length: 3 })
+        );
+        assert_eq!(
+            Radix::BASE16.decode("666f"),
+            Err(DecodeError::InvalidChar {
+                index: 3,
+                byte: b'f'
+            })
+        );
+        assert_eq!(
+            Radix::BASE64.decode("Zm9v=="),
+            Err(DecodeError::InvalidChar {
+                index: 4,
+                byte: b'='
+            })
+        );
+        assert_eq!(
+            Radix::BASE64.decode("Zm9vé"),
+            Err(DecodeError::NonAscii { index: 4 })
+        );
+    }
+
+    #[test]
+    fn lengths_do_not_overflow() {
+        for radix in [Radix::new(1, "01"), Radix::new(3, "01234567"), Radix::BASE64] {
+            let bits = radix.bits() as usize;
+            for len in 0..100 {
+                assert_eq!(radix.encoded_len(len), (len * 8).div_ceil(bits));
+                assert_eq!(radix.decoded_len(len), len * bits / 8);
+            }
+            assert_eq!(radix.encoded_len(usize::MAX), usize::MAX);
+            assert!(radix.decoded_len(usize::MAX) <= usize::MAX / 8 * bits + bits);
+        }
+        let max = isize::MAX as usize;
+        assert_eq!(Radix::BASE64.encoded_len(max / 4 * 3), max / 4 * 4);
+        assert_eq!(Radix::BASE16.encoded_len(max / 2), max / 2 * 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "exactly 2^bits symbols")]
+    fn wrong_symbol_count() {
+        Radix::new(4, "0123456789ABCDEFG");
+    }
+
+    #[test]
+    #[should_panic(expected = "1 to 6 bits")]
+    fn too_many_bits() {
+        Radix::new(7, "");
+    }
+}
@@ -0,0 +1,92 @@
+use alloc::string::String;
+use alloc::vec;
+use rand::Rng;
+
+use super::{decoded_len, Alphabet, Config};
+
+/// Generates a random string of `char_len` symbols that decodes without error, for fixtures
+/// and property tests. Requires the `rand` feature.
+///
+/// `char_len` is rounded down to the nearest length [`encode`](super::encode) can produce: a
+/// multiple of 8 for padded alphabets, otherwise any length except 1, 3 or 6 modulo 8. The
+/// string is the encoding of random bytes, so it is also canonical: padded alphabets get whole
+/// groups without padding, and the last symbol of an unpadded one has its unused bits clear.
+///
+/// ```
+/// use base32::{decode, random, Alphabet};
+///
+/// fn fixture<R: rand::Rng>(rng: &mut R) -> Vec<u8> {
+///     let key = random(Alphabet::Crockford, 26, rng);
+///     assert_eq!(key.len(), 26);
+///     decode(Alphabet::Crockford, &key).unwrap()
+/// }
+/// ```
+pub fn random<R: Rng + ?Sized>(alphabet: Alphabet, char_len: usize, rng: &mut R) -> String {
+    let config = Config::new(alphabet);
+    let len = if config.padding {
+        char_len / 8 * 8
+    } else {
+        char_len - usize::from(matches!(char_len % 8, 1 | 3 | 6))
+    };
+    let mut bytes = vec![0; decoded_len(len)];
+    rng.fill_bytes(&mut bytes);
+    config.encode(&bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{decode, normalize, Alphabet};
+    use super::random;
+    use core::convert::Infallible;
+    use rand::TryRng;
+
+    /// xorshift64, enough to exercise `random` without pulling in a generator crate.
+    struct XorShift(u64);
+
+    impl TryRng for XorShift {
+        type Error = Infallible;
+
+        fn try_next_u32(&mut self) -> Result<u32, Infallible> {
+            self.try_next_u64().map(|n| n as u32)
+        }
+
+        fn try_next_u64(&mut self) -> Result<u64, Infallible> {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            Ok(self.0)
+        }
+
+        fn try_fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), Infallible> {
+            for byte in dst {
+                *byte = self.try_next_u64()? as u8;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn random_decodes() {
+        let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+        let alphabets = [
+            Alphabet::Crockford,
+            Alphabet::Rfc4648 { padding: true },
+            Alphabet::Rfc4648Lower { padding: false },
+            #[cfg(feature = "zbase32")]
+            Alphabet::Z,
+        ];
+        for &alphabet in &alphabets {
+            for char_len in 0..40 {
+                let s = random(alphabet, char_len, &mut rng);
+                let expected = match (alphabet, char_len % 8) {
+                    (Alphabet::Rfc4648 { .. }, _) => char_len / 8 * 8,
+                    (_, 1) | (_, 3) | (_, 6) => char_len - 1,
+                    _ => char_len,
+                };
+                assert_eq!(s.len(), expected);
+                assert!(decode(alphabet, &s).is_some(), "{}", s);
+                assert_eq!(normalize(alphabet, &s).as_deref(), Some(&s[..]));
+            }
+        }
+    }
+}
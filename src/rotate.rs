@@ -0,0 +1,129 @@
+//! Encoding with a keyed rotation of the alphabet, for obfuscating IDs.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{ascii_string, Alphabet, Config, DecodeError, OUTPUT_CHUNK};
+
+/// How far group `group` of 8 symbols is rotated under `key`: output `group` of the SplitMix64
+/// generator seeded with `key`, modulo 32.
+fn offset(key: u64, group: usize) -> u8 {
+    let step = (group as u64).wrapping_add(1);
+    let mut z = key.wrapping_add(step.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    ((z ^ (z >> 31)) % 32) as u8
+}
+
+/// Encodes `data` like [`encode`](super::encode), then rotates the alphabet of every group of 8
+/// symbols by an offset derived from `key`, so the same bytes look different under different
+/// keys. Decode it with [`decode_rotated`] and the same key.
+///
+/// **This is obfuscation, not encryption.** It hides sequential IDs from casual inspection and
+/// nothing more: there are only 32 possible offsets per group, they repeat for every input
+/// under the same key, and anyone with a few encodings of known data can recover them. Do not
+/// use it to protect anything an adversary wants to read or forge; use a real cipher or MAC.
+///
+/// Group `i` (counting from 0) is rotated by the `i`-th output of SplitMix64 seeded with `key`,
+/// modulo 32: a symbol of value `v` is written as the symbol of value `(v + offset) % 32`.
+/// This derivation is part of the format and will not change. Padding is left as it is, so the
+/// output has the same length and padding as [`encode`](super::encode).
+///
+/// ```
+/// use base32::{decode_rotated, encode, encode_rotated, Alphabet};
+///
+/// let alphabet = Alphabet::Crockford;
+/// let id = 1234u32.to_be_bytes();
+/// let obfuscated = encode_rotated(alphabet, &id, 42);
+/// assert_eq!(obfuscated.len(), encode(alphabet, &id).len());
+/// assert_ne!(obfuscated, encode_rotated(alphabet, &id, 43));
+/// assert_eq!(decode_rotated(alphabet, &obfuscated, 42).unwrap(), id);
+/// ```
+pub fn encode_rotated(alphabet: Alphabet, data: &[u8], key: u64) -> String {
+    let config = Config::new(alphabet);
+    let symbols = alphabet.table().symbols();
+    let mut encoded = config.encode(data).into_bytes();
+    for (group, chunk) in encoded.chunks_mut(OUTPUT_CHUNK).enumerate() {
+        let offset = offset(key, group);
+        for c in chunk {
+            if let Some(value) = config.value(*c) {
+                *c = symbols[((value + offset) % 32) as usize];
+            }
+        }
+    }
+    ascii_string(encoded)
+}
+
+/// Decodes the output of [`encode_rotated`] made with the same `key`.
+///
+/// The input is accepted exactly as [`decode`](super::decode) would accept the unrotated
+/// encoding, including its case rules, and errors are reported at the same indices with the
+/// same bytes. With the wrong key, decoding usually succeeds but gives the wrong bytes.
+pub fn decode_rotated(alphabet: Alphabet, data: &str, key: u64) -> Result<Vec<u8>, DecodeError> {
+    let config = Config::new(alphabet);
+    let symbols = alphabet.table().symbols();
+    let mut data = data.as_bytes().to_vec();
+    // Only symbols are rotated back; padding and invalid bytes are kept for the decoder to
+    // judge, so errors point at the input as given.
+    for (group, chunk) in data.chunks_mut(OUTPUT_CHUNK).enumerate() {
+        let offset = offset(key, group);
+        for c in chunk {
+            if let Some(value) = config.value(*c) {
+                *c = symbols[((value + 32 - offset) % 32) as usize];
+            }
+        }
+    }
+    config.decode_bytes(&data).map(|decoded| decoded.data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{decode, encode, Alphabet, DecodeError};
+    use super::{decode_rotated, encode_rotated, offset};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn round_trip() {
+        fn test(data: Vec<u8>, key: u64, padding: bool) -> bool {
+            [Alphabet::Crockford, Alphabet::Rfc4648 { padding }]
+                .iter()
+                .all(|&alphabet| {
+                    let encoded = encode_rotated(alphabet, &data, key);
+                    encoded.len() == encode(alphabet, &data).len()
+                        && decode(alphabet, &encoded).is_some()
+                        && decode_rotated(alphabet, &encoded, key).unwrap() == data
+                })
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, u64, bool) -> bool);
+    }
+
+    #[test]
+    fn rotation() {
+        // SplitMix64 seeded with 0 starts with 0xE220A8397B1DCDAF.
+        assert_eq!(offset(0, 0), 0x0F);
+        let alphabet = Alphabet::Rfc4648 { padding: true };
+        // `MZXW6===` rotated by 15.
+        assert_eq!(encode_rotated(alphabet, b"foo", 0), "3IGFN===");
+        assert_eq!(decode_rotated(alphabet, "3IGFN===", 0).unwrap(), b"foo");
+        // Every group gets its own offset.
+        let encoded = encode_rotated(alphabet, &[0; 40], 7);
+        let groups = encoded.as_bytes().chunks(8).collect::<Vec<&[u8]>>();
+        assert!(groups.iter().any(|&group| group != groups[0]));
+    }
+
+    #[test]
+    fn errors_point_at_input() {
+        let alphabet = Alphabet::Crockford;
+        assert_eq!(
+            decode_rotated(alphabet, "csqpyru", 1),
+            Err(DecodeError::InvalidChar {
+                index: 6,
+                byte: b'u'
+            })
+        );
+        assert_eq!(
+            decode_rotated(alphabet, "CSQPé", 1),
+            Err(DecodeError::NonAscii { index: 4 })
+        );
+    }
+}
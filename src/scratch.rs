@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use super::{Alphabet, Config, DecodeError};
+
+/// Scratch buffers that grew past this are dropped after use rather than kept for the thread's
+/// lifetime, so one large input does not pin its memory.
+const MAX_RETAINED: usize = 64 * 1024;
+
+std::thread_local! {
+    static SCRATCH: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Decodes `data` into a buffer kept per thread and reused across calls, and passes the bytes
+/// to `f`, returning what it returns. For services decoding many small values this avoids an
+/// allocation per call once the buffer has grown to fit.
+///
+/// The slice is only valid inside `f`; copy it out to keep it. If `f` itself calls
+/// `decode_scratch`, the inner call decodes into a fresh `Vec` instead. Requires the `std`
+/// feature.
+///
+/// ```
+/// use base32::{decode_scratch, Alphabet};
+///
+/// let len = decode_scratch(Alphabet::Crockford, "CSQPYRK1E8", |bytes| {
+///     assert_eq!(bytes, b"foobar");
+///     bytes.len()
+/// });
+/// assert_eq!(len, Ok(6));
+/// ```
+pub fn decode_scratch<R, F: FnOnce(&[u8]) -> R>(
+    alphabet: Alphabet,
+    data: &str,
+    f: F,
+) -> Result<R, DecodeError> {
+    let config = Config::new(alphabet);
+    SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+        Ok(mut buf) => {
+            buf.clear();
+            let result = config.decode_append(data.as_bytes(), &mut buf).map(|_| f(&buf));
+            if buf.capacity() > MAX_RETAINED {
+                *buf = Vec::new();
+            }
+            result
+        }
+        Err(_) => config.decode(data).map(|buf| f(&buf)),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{decode, encode, Alphabet};
+    use super::{decode_scratch, MAX_RETAINED, SCRATCH};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn matches_decode() {
+        fn test(data: Vec<u8>, text: String, padding: bool) -> bool {
+            let alphabet = Alphabet::Rfc4648 { padding };
+            let encoded = encode(alphabet, &data);
+            decode_scratch(alphabet, &encoded, <[u8]>::to_vec) == Ok(data)
+                && decode_scratch(alphabet, &text, <[u8]>::to_vec).ok() == decode(alphabet, &text)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, String, bool) -> bool);
+    }
+
+    #[test]
+    fn reuses_buffer() {
+        let alphabet = Alphabet::Crockford;
+        decode_scratch(alphabet, "CSQPYRK1E8", |_| ()).unwrap();
+        let capacity = SCRATCH.with(|scratch| scratch.borrow().capacity());
+        assert!(capacity >= 6);
+        decode_scratch(alphabet, "CSQPY", |bytes| assert_eq!(bytes, b"foo")).unwrap();
+        assert_eq!(SCRATCH.with(|scratch| scratch.borrow().capacity()), capacity);
+
+        let large = "0".repeat(MAX_RETAINED * 2);
+        decode_scratch(alphabet, &large, |bytes| assert!(bytes.iter().all(|&b| b == 0))).unwrap();
+        assert_eq!(SCRATCH.with(|scratch| scratch.borrow().capacity()), 0);
+    }
+
+    #[test]
+    fn nested() {
+        let alphabet = Alphabet::Crockford;
+        let inner = decode_scratch(alphabet, "CSQPY", |outer| {
+            decode_scratch(alphabet, "C9GQ4", |inner| {
+                assert_eq!(outer, b"foo");
+                inner.to_vec()
+            })
+        });
+        assert_eq!(inner, Ok(Ok(b"bar".to_vec())));
+    }
+}
@@ -0,0 +1,225 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::{mem, str};
+#[cfg(feature = "std")]
+use std::io;
+
+/// Size of the buffer [`IoSink`] collects symbols in before writing them out.
+#[cfg(feature = "std")]
+const IO_BUFFER: usize = 4096;
+
+/// A destination for encoded symbols. The encoding loop in
+/// [`Config::encode_into`](super::Config::encode_into) is written once against this trait, and
+/// each output target only has to say how to append bytes to itself.
+///
+/// Encoders only ever push ASCII.
+pub(crate) trait ByteSink {
+    fn push_slice(&mut self, bytes: &[u8]);
+
+    fn push(&mut self, byte: u8) {
+        self.push_slice(&[byte]);
+    }
+}
+
+impl ByteSink for Vec<u8> {
+    fn push_slice(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+
+    fn push(&mut self, byte: u8) {
+        Vec::push(self, byte);
+    }
+}
+
+impl ByteSink for String {
+    fn push_slice(&mut self, bytes: &[u8]) {
+        self.extend(bytes.iter().map(|&byte| char::from(byte)));
+    }
+
+    fn push(&mut self, byte: u8) {
+        String::push(self, char::from(byte));
+    }
+}
+
+/// Writes to the front of the slice and advances past what was written, like `io::Write` for
+/// `&mut [u8]`. The caller sizes the slice with `encoded_len`; running out of room panics.
+impl ByteSink for &mut [u8] {
+    fn push_slice(&mut self, bytes: &[u8]) {
+        let (head, tail) = mem::take(self).split_at_mut(bytes.len());
+        head.copy_from_slice(bytes);
+        *self = tail;
+    }
+}
+
+/// Writes to a formatter, keeping the first error for [`finish`](FmtSink::finish).
+pub(crate) struct FmtSink<'a, 'b: 'a> {
+    f: &'a mut fmt::Formatter<'b>,
+    result: fmt::Result,
+}
+
+impl<'a, 'b> FmtSink<'a, 'b> {
+    pub(crate) fn new(f: &'a mut fmt::Formatter<'b>) -> FmtSink<'a, 'b> {
+        FmtSink { f, result: Ok(()) }
+    }
+
+    pub(crate) fn finish(self) -> fmt::Result {
+        self.result
+    }
+}
+
+impl<'a, 'b> ByteSink for FmtSink<'a, 'b> {
+    fn push_slice(&mut self, bytes: &[u8]) {
+        if self.result.is_ok() {
+            self.result = match str::from_utf8(bytes) {
+                Ok(s) => self.f.write_str(s),
+                Err(_) => Err(fmt::Error),
+            };
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.result.is_ok() {
+            self.result = self.f.write_char(char::from(byte));
+        }
+    }
+}
+
+/// Writes to an `io::Write` through a fixed buffer, so the inner writer sees a few large writes
+/// rather than one per group. Keeps the first error for [`finish`](IoSink::finish), which also
+/// writes out what is still buffered.
+#[cfg(feature = "std")]
+pub(crate) struct IoSink<'a, W: 'a> {
+    inner: &'a mut W,
+    buf: [u8; IO_BUFFER],
+    len: usize,
+    result: io::Result<()>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, W: io::Write> IoSink<'a, W> {
+    pub(crate) fn new(inner: &'a mut W) -> IoSink<'a, W> {
+        IoSink {
+            inner,
+            buf: [0; IO_BUFFER],
+            len: 0,
+            result: Ok(()),
+        }
+    }
+
+    fn write_buffered(&mut self) {
+        if self.result.is_ok() {
+            self.result = self.inner.write_all(&self.buf[..self.len]);
+        }
+        self.len = 0;
+    }
+
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        if self.len > 0 {
+            self.write_buffered();
+        }
+        mem::replace(&mut self.result, Ok(()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, W: io::Write> ByteSink for IoSink<'a, W> {
+    fn push_slice(&mut self, bytes: &[u8]) {
+        if self.len + bytes.len() > IO_BUFFER {
+            self.write_buffered();
+        }
+        if self.result.is_err() {
+            return;
+        }
+        if bytes.len() > IO_BUFFER {
+            self.result = self.inner.write_all(bytes);
+            return;
+        }
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{encode, Alphabet, Config};
+    use super::ByteSink;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn sinks_match_encode() {
+        fn test(data: Vec<u8>, padding: bool) -> bool {
+            let config = Config::new(Alphabet::Crockford).padding(padding);
+            let expected = config.encode(&data);
+
+            let mut string = String::new();
+            config.encode_into(&data, &mut string);
+
+            let mut buf = Vec::new();
+            buf.resize(expected.len() + 3, b'!');
+            let mut slice = &mut buf[..];
+            config.encode_into(&data, &mut slice);
+            let remaining = slice.len();
+
+            string == expected && remaining == 3 && buf[..expected.len()] == *expected.as_bytes()
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, bool) -> bool)
+    }
+
+    #[test]
+    fn push_bytes() {
+        let mut string = String::new();
+        ByteSink::push(&mut string, b'A');
+        string.push_slice(b"BC");
+        assert_eq!(string, "ABC");
+
+        let mut buf = [0; 4];
+        let mut slice = &mut buf[..];
+        ByteSink::push(&mut slice, b'Z');
+        slice.push_slice(b"0");
+        assert_eq!(slice.len(), 2);
+        assert_eq!(&buf[..2], encode(Alphabet::Crockford, &[0xF8]).as_bytes());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_sink() {
+        use super::IoSink;
+        use std::io::{self, ErrorKind, Write};
+
+        fn test(data: Vec<u8>, padding: bool) -> bool {
+            let config = Config::new(Alphabet::Crockford).padding(padding);
+            let mut out = Vec::new();
+            let mut sink = IoSink::new(&mut out);
+            config.encode_into(&data, &mut sink);
+            sink.finish().is_ok() && out == config.encode(&data).as_bytes()
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, bool) -> bool);
+
+        // Larger than the buffer, so the failing write happens before `finish`.
+        let data = [0xA5; 8000];
+        let mut out = [0u8; 100];
+        let mut slice = &mut out[..];
+        let mut sink = IoSink::new(&mut slice);
+        Config::new(Alphabet::Crockford).encode_into(&data, &mut sink);
+        assert_eq!(sink.finish().unwrap_err().kind(), ErrorKind::WriteZero);
+
+        struct Fail(usize);
+        impl Write for Fail {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                self.0 += 1;
+                Err(io::Error::other("fail"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        // Only the first error is kept, and nothing is written after it.
+        let mut fail = Fail(0);
+        let mut sink = IoSink::new(&mut fail);
+        Config::new(Alphabet::Crockford).encode_into(&data, &mut sink);
+        assert!(sink.finish().is_err());
+        assert_eq!(fail.0, 1);
+    }
+}
@@ -0,0 +1,91 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+
+/// Decoded bytes stored inline when there are at most `N` of them, and on the heap otherwise.
+/// Returned by [`Config::decode_small`](super::Config::decode_small).
+#[derive(Clone)]
+pub struct SmallBytes<const N: usize> {
+    repr: Repr<N>,
+}
+
+#[derive(Clone)]
+enum Repr<const N: usize> {
+    Inline { buf: [u8; N], len: usize },
+    Heap(Vec<u8>),
+}
+
+impl<const N: usize> SmallBytes<N> {
+    pub(crate) fn inline(buf: [u8; N], len: usize) -> SmallBytes<N> {
+        SmallBytes {
+            repr: Repr::Inline { buf, len },
+        }
+    }
+
+    /// Stores `data` inline if it fits, otherwise keeps the allocation.
+    pub(crate) fn from_vec(data: Vec<u8>) -> SmallBytes<N> {
+        if data.len() <= N {
+            let mut buf = [0; N];
+            buf[..data.len()].copy_from_slice(&data);
+            SmallBytes::inline(buf, data.len())
+        } else {
+            SmallBytes {
+                repr: Repr::Heap(data),
+            }
+        }
+    }
+
+    /// Returns whether the bytes did not fit in `N` and were allocated on the heap.
+    pub fn spilled(&self) -> bool {
+        match self.repr {
+            Repr::Inline { .. } => false,
+            Repr::Heap(_) => true,
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        match self.repr {
+            Repr::Inline { buf, len } => buf[..len].to_vec(),
+            Repr::Heap(data) => data,
+        }
+    }
+}
+
+impl<const N: usize> Deref for SmallBytes<N> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self.repr {
+            Repr::Inline { ref buf, len } => &buf[..len],
+            Repr::Heap(ref data) => data,
+        }
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for SmallBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize> fmt::Debug for SmallBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SmallBytes;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn from_vec_spills_past_capacity() {
+        let small = SmallBytes::<4>::from_vec(Vec::from(&b"abcd"[..]));
+        assert!(!small.spilled());
+        assert_eq!(&*small, b"abcd");
+        let large = SmallBytes::<4>::from_vec(Vec::from(&b"abcde"[..]));
+        assert!(large.spilled());
+        assert_eq!(large.into_vec(), b"abcde");
+    }
+}
@@ -0,0 +1,151 @@
+use alloc::vec::Vec;
+
+use super::{Alphabet, Config, DecodeError, OUTPUT_CHUNK};
+
+/// An incremental decoder driven by the caller: text is pushed in pieces of any size and the
+/// bytes of every group completed so far come back, without a `Read` or `Write` to hold on to,
+/// e.g. in an async task.
+///
+/// A group containing padding is held back in case more input follows, and it and any partial
+/// group are decoded by [`finish`](DecodeState::finish). The output, put together, is the same
+/// as that of [`decode`](super::decode) on all of the text. Error indices count from the first
+/// byte pushed. [`DecodeWriter`](super::DecodeWriter) is the `io::Write` form of this.
+///
+/// ```
+/// use base32::{Alphabet, DecodeState};
+///
+/// let mut state = DecodeState::new(Alphabet::Rfc4648 { padding: true });
+/// assert_eq!(state.push(b"MZXW").unwrap(), b"");
+/// assert_eq!(state.push(b"6YTBOI==").unwrap(), b"fooba");
+/// assert_eq!(state.push(b"====").unwrap(), b"");
+/// assert_eq!(state.finish().unwrap(), b"r");
+/// ```
+#[derive(Clone)]
+pub struct DecodeState {
+    config: Config,
+    pending: [u8; OUTPUT_CHUNK],
+    pending_len: usize,
+    offset: usize,
+}
+
+impl DecodeState {
+    pub fn new(alphabet: Alphabet) -> DecodeState {
+        DecodeState {
+            config: Config::new(alphabet),
+            pending: [0; OUTPUT_CHUNK],
+            pending_len: 0,
+            offset: 0,
+        }
+    }
+
+    /// Decodes every group completed by `chunk` and returns the bytes. The rest of `chunk` is
+    /// kept until the next call.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        let mut ret = Vec::with_capacity((self.pending_len + chunk.len()) / OUTPUT_CHUNK * 5);
+        self.push_into(chunk, &mut ret)?;
+        Ok(ret)
+    }
+
+    /// Decodes the held back group, if any, and returns its bytes.
+    #[must_use = "the bytes of the held back group are only returned here"]
+    pub fn finish(mut self) -> Result<Vec<u8>, DecodeError> {
+        let mut ret = Vec::new();
+        self.finish_into(&mut ret)?;
+        Ok(ret)
+    }
+
+    /// Like [`push`](DecodeState::push), but appends to `out`. On error `out` holds the bytes
+    /// of the groups before the invalid one.
+    pub(crate) fn push_into(
+        &mut self,
+        mut input: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), DecodeError> {
+        while !input.is_empty() {
+            // A held back padded group turned out not to be the last one.
+            if self.pending_len == OUTPUT_CHUNK {
+                self.decode_group(out)?;
+            }
+            let n = (OUTPUT_CHUNK - self.pending_len).min(input.len());
+            self.pending[self.pending_len..self.pending_len + n].copy_from_slice(&input[..n]);
+            self.pending_len += n;
+            input = &input[n..];
+            let padded = self.config.padding && self.pending.contains(&b'=');
+            if self.pending_len == OUTPUT_CHUNK && !padded {
+                self.decode_group(out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [`finish`](DecodeState::finish), but appends to `out` and leaves the state empty.
+    pub(crate) fn finish_into(&mut self, out: &mut Vec<u8>) -> Result<(), DecodeError> {
+        let offset = self.offset;
+        let pending_len = self.pending_len;
+        self.pending_len = 0;
+        self.config
+            .decode_append(&self.pending[..pending_len], out)
+            .map(drop)
+            .map_err(|err| err.map_index(|i| offset + i))
+    }
+
+    /// Decodes the full pending group into `out`.
+    fn decode_group(&mut self, out: &mut Vec<u8>) -> Result<(), DecodeError> {
+        self.config
+            .decode_chunks(&self.pending, self.offset, |chunk| out.extend_from_slice(chunk))?;
+        self.pending_len = 0;
+        self.offset += OUTPUT_CHUNK;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{decode, encode, Alphabet, DecodeError};
+    use super::DecodeState;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn matches_decode() {
+        fn test(data: Vec<u8>, split: Vec<usize>, padding: bool) -> bool {
+            let alphabet = Alphabet::Rfc4648 { padding };
+            let encoded = encode(alphabet, &data);
+            let mut state = DecodeState::new(alphabet);
+            let mut ret = Vec::new();
+            let mut rest = encoded.as_bytes();
+            for n in split {
+                let n = n % (rest.len() + 1);
+                ret.extend(state.push(&rest[..n]).unwrap());
+                rest = &rest[n..];
+            }
+            ret.extend(state.push(rest).unwrap());
+            ret.extend(state.finish().unwrap());
+            ret == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, Vec<usize>, bool) -> bool)
+    }
+
+    #[test]
+    fn invalid() {
+        let alphabet = Alphabet::Crockford;
+        let mut state = DecodeState::new(alphabet);
+        assert_eq!(state.push(b"CSQPYRK1E8").unwrap(), decode(alphabet, "CSQPYRK1").unwrap());
+        assert_eq!(
+            state.push(b"CSQPYRKU000000"),
+            Err(DecodeError::InvalidChar {
+                index: 17,
+                byte: b'U'
+            })
+        );
+
+        let mut state = DecodeState::new(Alphabet::Rfc4648 { padding: true });
+        assert_eq!(state.push(b"MZXW6YTBO!").unwrap(), b"fooba");
+        assert_eq!(
+            state.finish(),
+            Err(DecodeError::InvalidChar {
+                index: 9,
+                byte: b'!'
+            })
+        );
+    }
+}
@@ -0,0 +1,966 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::io::{self, Read, Write};
+
+use super::sink::IoSink;
+use super::{
+    ascii_string, decoded_len, Alphabet, Config, DecodeError, DecodeState, INPUT_CHUNK,
+    OUTPUT_CHUNK,
+};
+
+/// Number of input bytes encoded per call to the underlying writer.
+const ENCODE_BATCH: usize = INPUT_CHUNK * 1024;
+/// Number of input characters decoded per call to the underlying writer.
+const DECODE_BATCH: usize = OUTPUT_CHUNK * 128;
+
+/// Callback passed the input bytes as [`EncodeWriter`] encodes them.
+type Observer<'a> = dyn FnMut(&[u8]) + Send + 'a;
+
+/// A writer that base32 encodes everything written to it and forwards the text to `inner`.
+///
+/// Input is encoded in whole 5-byte groups as it arrives; a trailing partial group is kept
+/// until [`finish`](EncodeWriter::finish) is called, which also applies padding. Dropping the
+/// writer without calling `finish` writes the remainder but ignores any error doing so.
+///
+/// ```
+/// use std::io::Write;
+/// use base32::{Alphabet, EncodeWriter};
+///
+/// let mut writer = EncodeWriter::new(Alphabet::Rfc4648 { padding: true }, Vec::new());
+/// writer.write_all(b"foo").unwrap();
+/// writer.write_all(b"bar").unwrap();
+/// assert_eq!(writer.finish().unwrap(), b"MZXW6YTBOI======");
+/// ```
+pub struct EncodeWriter<'a, W: Write> {
+    config: Config,
+    inner: Option<W>,
+    pending: [u8; 5],
+    pending_len: usize,
+    /// Whether the tail has been written, or failed to be, so `Drop` does not repeat it.
+    finished: bool,
+    processed: usize,
+    progress: Option<Box<dyn FnMut(usize) + Send + 'a>>,
+    observer: Option<Box<Observer<'a>>>,
+}
+
+impl<'a, W: Write> EncodeWriter<'a, W> {
+    pub fn new(alphabet: Alphabet, inner: W) -> EncodeWriter<'a, W> {
+        EncodeWriter::with_config(Config::new(alphabet), inner)
+    }
+
+    pub fn with_config(config: Config, inner: W) -> EncodeWriter<'a, W> {
+        EncodeWriter {
+            config,
+            inner: Some(inner),
+            pending: [0; 5],
+            pending_len: 0,
+            finished: false,
+            processed: 0,
+            progress: None,
+            observer: None,
+        }
+    }
+
+    /// Registers a callback that is called each time a block of output is written to the inner
+    /// writer, with the total number of input bytes encoded so far. The last call happens in
+    /// [`finish`](EncodeWriter::finish), for the trailing partial group.
+    ///
+    /// The callback may borrow local state for as long as the writer lives, and must be `Send`
+    /// so the writer can still be moved to another thread.
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use base32::{Alphabet, EncodeWriter};
+    ///
+    /// let mut last = 0;
+    /// let mut writer = EncodeWriter::new(Alphabet::Crockford, Vec::new())
+    ///     .on_progress(|processed| last = processed);
+    /// writer.write_all(&[0; 12]).unwrap();
+    /// writer.finish().unwrap();
+    /// assert_eq!(last, 12);
+    /// ```
+    pub fn on_progress<F: FnMut(usize) + Send + 'a>(
+        mut self,
+        progress: F,
+    ) -> EncodeWriter<'a, W> {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Registers a callback that is passed the input bytes as they are encoded, so a checksum
+    /// of the data can be computed in the same pass. Every byte written is passed exactly once
+    /// and in order, a trailing partial group only in [`finish`](EncodeWriter::finish).
+    ///
+    /// Like [`on_progress`](EncodeWriter::on_progress), the callback may borrow local state,
+    /// such as a hasher, and must be `Send`.
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use base32::{Alphabet, EncodeWriter};
+    ///
+    /// let mut sum = 0u32;
+    /// let mut writer = EncodeWriter::new(Alphabet::Crockford, Vec::new())
+    ///     .with_observer(|input| sum += input.iter().map(|&b| b as u32).sum::<u32>());
+    /// writer.write_all(b"foobar").unwrap();
+    /// writer.finish().unwrap();
+    /// assert_eq!(sum, b"foobar".iter().map(|&b| b as u32).sum());
+    /// ```
+    pub fn with_observer<F: FnMut(&[u8]) + Send + 'a>(
+        mut self,
+        observer: F,
+    ) -> EncodeWriter<'a, W> {
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
+    /// The number of bytes written so far, including a partial group not yet encoded.
+    pub fn total_in(&self) -> usize {
+        self.processed + self.pending_len
+    }
+
+    /// Encodes and writes the buffered partial group, followed by a `\n` if the config asks for
+    /// a [trailing newline](Config::trailing_newline), then returns the inner writer.
+    ///
+    /// The tail is only attempted once: if writing it fails, dropping the writer does not try
+    /// again, so a partially written tail is never followed by a second copy or newline.
+    #[must_use = "an error writing the last group is only reported here"]
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_tail()?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    fn write_tail(&mut self) -> io::Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        let pending_len = self.pending_len;
+        self.pending_len = 0;
+        let pending = self.pending;
+        self.write_block(&pending[..pending_len])?;
+        if self.config.trailing_newline {
+            self.inner.as_mut().unwrap().write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Encodes `input` and writes it to the inner writer. `input` must be a whole number of
+    /// groups unless it is the end of the stream.
+    fn write_block(&mut self, input: &[u8]) -> io::Result<()> {
+        let mut sink = IoSink::new(self.inner.as_mut().unwrap());
+        self.config.encode_into(input, &mut sink);
+        sink.finish()?;
+        if let Some(ref mut observer) = self.observer {
+            observer(input);
+        }
+        self.processed += input.len();
+        if let Some(ref mut progress) = self.progress {
+            progress(self.processed);
+        }
+        Ok(())
+    }
+}
+
+impl<'a, W: Write> Write for EncodeWriter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut input = buf;
+
+        if self.pending_len > 0 {
+            let n = (5 - self.pending_len).min(input.len());
+            self.pending[self.pending_len..self.pending_len + n].copy_from_slice(&input[..n]);
+            self.pending_len += n;
+            input = &input[n..];
+            if self.pending_len < 5 {
+                return Ok(buf.len());
+            }
+            self.pending_len = 0;
+            let pending = self.pending;
+            self.write_block(&pending)?;
+        }
+
+        while input.len() >= 5 {
+            let n = (input.len() - input.len() % 5).min(ENCODE_BATCH);
+            self.write_block(&input[..n])?;
+            input = &input[n..];
+        }
+
+        self.pending[..input.len()].copy_from_slice(input);
+        self.pending_len = input.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+impl<'a, W: Write> Drop for EncodeWriter<'a, W> {
+    fn drop(&mut self) {
+        let _ = self.write_tail();
+    }
+}
+
+/// A writer that decodes base32 text written to it and forwards the bytes to `inner`.
+///
+/// Each group of 8 characters is decoded and written as soon as its last character arrives,
+/// so a consumer downstream sees the bytes with as little delay as the encoding allows. A
+/// group containing padding is held back in case more input follows, and it and any partial
+/// group are decoded by [`finish`](DecodeWriter::finish). The output is the same as that of
+/// [`decode`](super::decode) on all of the text.
+///
+/// Invalid input is reported as an [`io::ErrorKind::InvalidData`] error wrapping the
+/// [`DecodeError`](super::DecodeError), whose index counts from the first byte written.
+/// Dropping the writer without calling `finish` decodes the remainder but ignores any error.
+///
+/// ```
+/// use std::io::Write;
+/// use base32::{Alphabet, DecodeWriter};
+///
+/// let mut writer = DecodeWriter::new(Alphabet::Rfc4648 { padding: true }, Vec::new());
+/// writer.write_all(b"MZXW6YTB").unwrap();
+/// assert_eq!(writer.get_ref(), b"fooba");
+/// writer.write_all(b"OI======").unwrap();
+/// assert_eq!(writer.finish().unwrap(), b"foobar");
+/// ```
+pub struct DecodeWriter<W: Write> {
+    state: DecodeState,
+    inner: Option<W>,
+    output: Vec<u8>,
+}
+
+impl<W: Write> DecodeWriter<W> {
+    pub fn new(alphabet: Alphabet, inner: W) -> DecodeWriter<W> {
+        DecodeWriter {
+            state: DecodeState::new(alphabet),
+            inner: Some(inner),
+            output: Vec::new(),
+        }
+    }
+
+    /// The inner writer, holding the bytes decoded so far.
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Decodes and writes the buffered group, then returns the inner writer.
+    #[must_use = "an error decoding or writing the last group is only reported here"]
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_tail()?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    fn write_tail(&mut self) -> io::Result<()> {
+        if self.inner.is_none() {
+            return Ok(());
+        }
+        self.output.clear();
+        self.state
+            .finish_into(&mut self.output)
+            .map_err(invalid_data)?;
+        self.inner.as_mut().unwrap().write_all(&self.output)
+    }
+}
+
+impl<W: Write> Write for DecodeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.clear();
+        let decoded = self.state.push_into(buf, &mut self.output).map_err(invalid_data);
+        // The bytes before an invalid character are still written.
+        self.inner.as_mut().unwrap().write_all(&self.output)?;
+        decoded.map(|()| buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+impl<W: Write> Drop for DecodeWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.write_tail();
+    }
+}
+
+/// A reader that reads bytes from `inner` and yields their base32 encoding, the `Read`
+/// counterpart of [`EncodeWriter`], e.g. for [`io::copy`] from a file into a socket.
+///
+/// Whole 5-byte groups are encoded as they are read. The trailing partial group, its padding
+/// and a [trailing newline](Config::trailing_newline) follow once `inner` reaches the end, so
+/// reading to the end gives exactly the output of [`Config::encode`].
+///
+/// ```
+/// use std::io::{self, Read};
+/// use base32::{Alphabet, EncodeReader};
+///
+/// let mut reader = EncodeReader::new(Alphabet::Rfc4648 { padding: true }, &b"foobar"[..]);
+/// let mut out = Vec::new();
+/// io::copy(&mut reader, &mut out).unwrap();
+/// assert_eq!(out, b"MZXW6YTBOI======");
+/// ```
+pub struct EncodeReader<R: Read> {
+    config: Config,
+    inner: R,
+    /// Bytes read but not yet encoded, at most a partial group between calls.
+    input: Vec<u8>,
+    output: Vec<u8>,
+    output_pos: usize,
+    done: bool,
+}
+
+impl<R: Read> EncodeReader<R> {
+    pub fn new(alphabet: Alphabet, inner: R) -> EncodeReader<R> {
+        EncodeReader::with_config(Config::new(alphabet), inner)
+    }
+
+    pub fn with_config(config: Config, inner: R) -> EncodeReader<R> {
+        EncodeReader {
+            config,
+            inner,
+            input: Vec::with_capacity(ENCODE_BATCH),
+            output: Vec::new(),
+            output_pos: 0,
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads from `inner` and encodes what it can into `output`, which may stay empty if only
+    /// part of a group was read.
+    fn fill(&mut self) -> io::Result<()> {
+        self.output.clear();
+        self.output_pos = 0;
+        let start = self.input.len();
+        self.input.resize(ENCODE_BATCH, 0);
+        let n = match read_retrying(&mut self.inner, &mut self.input[start..]) {
+            Ok(n) => n,
+            Err(err) => {
+                self.input.truncate(start);
+                return Err(err);
+            }
+        };
+        self.input.truncate(start + n);
+        if n == 0 {
+            self.config.encode_into(&self.input, &mut self.output);
+            if self.config.trailing_newline {
+                self.output.push(b'\n');
+            }
+            self.input.clear();
+            self.done = true;
+        } else {
+            let len = self.input.len() / INPUT_CHUNK * INPUT_CHUNK;
+            self.config.encode_into(&self.input[..len], &mut self.output);
+            self.input.drain(..len);
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for EncodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.output_pos == self.output.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        let n = buf.len().min(self.output.len() - self.output_pos);
+        buf[..n].copy_from_slice(&self.output[self.output_pos..self.output_pos + n]);
+        self.output_pos += n;
+        Ok(n)
+    }
+}
+
+/// A reader that reads base32 text from `inner` and yields the decoded bytes, the `Read`
+/// counterpart of [`DecodeWriter`].
+///
+/// Each group is decoded as soon as it has been read, with the held back last group decoded
+/// once `inner` reaches the end, so reading to the end gives exactly the output of
+/// [`decode`](super::decode). Invalid input is reported as an [`io::ErrorKind::InvalidData`]
+/// error wrapping the [`DecodeError`](super::DecodeError), after the bytes decoded before the
+/// invalid character.
+///
+/// ```
+/// use std::io::{self, Read};
+/// use base32::{Alphabet, DecodeReader};
+///
+/// let mut reader = DecodeReader::new(Alphabet::Crockford, &b"CSQPYRK1E8"[..]);
+/// let mut out = Vec::new();
+/// io::copy(&mut reader, &mut out).unwrap();
+/// assert_eq!(out, b"foobar");
+/// ```
+pub struct DecodeReader<R: Read> {
+    state: DecodeState,
+    inner: R,
+    input: [u8; DECODE_BATCH],
+    output: Vec<u8>,
+    output_pos: usize,
+    /// An error to report once the bytes decoded before it have been read.
+    error: Option<DecodeError>,
+    done: bool,
+}
+
+impl<R: Read> DecodeReader<R> {
+    pub fn new(alphabet: Alphabet, inner: R) -> DecodeReader<R> {
+        DecodeReader {
+            state: DecodeState::new(alphabet),
+            inner,
+            input: [0; DECODE_BATCH],
+            output: Vec::new(),
+            output_pos: 0,
+            error: None,
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        self.output.clear();
+        self.output_pos = 0;
+        let n = read_retrying(&mut self.inner, &mut self.input)?;
+        let decoded = if n == 0 {
+            self.done = true;
+            self.state.finish_into(&mut self.output)
+        } else {
+            self.state.push_into(&self.input[..n], &mut self.output)
+        };
+        if let Err(err) = decoded {
+            self.error = Some(err);
+            self.done = true;
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.output_pos == self.output.len() {
+            if let Some(err) = self.error.take() {
+                return Err(invalid_data(err));
+            }
+            if self.done {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        let n = buf.len().min(self.output.len() - self.output_pos);
+        buf[..n].copy_from_slice(&self.output[self.output_pos..self.output_pos + n]);
+        self.output_pos += n;
+        Ok(n)
+    }
+}
+
+/// Reads from `reader`, retrying reads that were interrupted.
+fn read_retrying<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match reader.read(buf) {
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            result => return result,
+        }
+    }
+}
+
+fn invalid_data(err: DecodeError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Reads `reader` to the end and returns its encoding.
+///
+/// The input is encoded as it is read rather than being collected into a buffer first.
+pub fn encode_from_reader<R: Read>(alphabet: Alphabet, mut reader: R) -> io::Result<String> {
+    let mut writer = EncodeWriter::new(alphabet, Vec::new());
+    io::copy(&mut reader, &mut writer)?;
+    Ok(ascii_string(writer.finish()?))
+}
+
+/// Decodes `data` and writes the bytes to `out` in batches, without collecting them into a
+/// `Vec`. Returns the number of bytes written.
+///
+/// Invalid input is reported as an [`io::ErrorKind::InvalidData`] error wrapping the
+/// [`DecodeError`](super::DecodeError). Bytes decoded before the invalid character may already
+/// have been written by then.
+pub fn decode_to_writer<W: Write>(
+    alphabet: Alphabet,
+    data: &str,
+    out: &mut W,
+) -> io::Result<usize> {
+    let config = Config::new(alphabet);
+    let (data, _) = config.strip_padding(data.as_bytes());
+    config
+        .check_length(data.len())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let output_length = decoded_len(data.len());
+    let mut remaining = output_length;
+    let mut buf = [0u8; DECODE_BATCH / 8 * 5];
+    for (batch_index, batch) in data.chunks(DECODE_BATCH).enumerate() {
+        let mut len = 0;
+        config
+            .decode_chunks(batch, batch_index * DECODE_BATCH, |chunk| {
+                buf[len..len + 5].copy_from_slice(chunk);
+                len += 5;
+            })
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let len = len.min(remaining);
+        out.write_all(&buf[..len])?;
+        remaining -= len;
+    }
+    Ok(output_length)
+}
+
+/// Decodes base32 text in the `from` alphabet read from `input` and writes it re-encoded in the
+/// `to` alphabet to `output`, without holding more than one batch of either in memory.
+///
+/// Invalid input is reported as an [`io::ErrorKind::InvalidData`] error wrapping the
+/// [`DecodeError`](super::DecodeError), whose index counts from the start of `input`. Output
+/// for the input before the error may already have been written by then.
+///
+/// ```
+/// use base32::{transcode, Alphabet};
+///
+/// let input = &b"MZXW6YTBOI======"[..];
+/// let mut out = Vec::new();
+/// transcode(Alphabet::Rfc4648 { padding: true }, Alphabet::Crockford, input, &mut out).unwrap();
+/// assert_eq!(out, b"CSQPYRK1E8");
+/// ```
+pub fn transcode<R: Read, W: Write>(
+    from: Alphabet,
+    to: Alphabet,
+    mut input: R,
+    output: W,
+) -> io::Result<()> {
+    let config = Config::new(from);
+    let mut writer = EncodeWriter::new(to, output);
+    let mut buf = [0u8; DECODE_BATCH];
+    let mut pending = Vec::with_capacity(DECODE_BATCH + OUTPUT_CHUNK);
+    let mut decoded = Vec::with_capacity(DECODE_BATCH / OUTPUT_CHUNK * INPUT_CHUNK);
+    let mut offset = 0;
+    loop {
+        let n = match input.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        pending.extend_from_slice(&buf[..n]);
+
+        // The last group may be padded or partial, so it is only decoded once the input ends.
+        // Everything before it is whole groups and is decoded as it arrives.
+        let len = pending.len().saturating_sub(1) / OUTPUT_CHUNK * OUTPUT_CHUNK;
+        if len > 0 {
+            decoded.clear();
+            config
+                .decode_chunks(&pending[..len], offset, |chunk| {
+                    decoded.extend_from_slice(chunk)
+                })
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            writer.write_all(&decoded)?;
+            pending.drain(..len);
+            offset += len;
+        }
+    }
+    let tail = config
+        .decode_bytes(&pending)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.map_index(|i| offset + i)))?;
+    writer.write_all(&tail.data)?;
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::Alphabet::{Crockford, Rfc4648};
+    use super::super::{decode, encode, Config, DecodeError};
+    use super::{
+        decode_to_writer, encode_from_reader, transcode, DecodeReader, DecodeWriter, EncodeReader,
+        EncodeWriter,
+    };
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+    use std::io::{self, ErrorKind, Read, Write};
+    use std::sync::Mutex;
+
+    /// A reader that returns at most `limit` bytes per read, to exercise batch boundaries.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        limit: usize,
+    }
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.limit).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn writer_matches_encode() {
+        fn test(data: Vec<u8>, split: Vec<usize>) -> bool {
+            let mut writer = EncodeWriter::new(Rfc4648 { padding: true }, Vec::new());
+            let mut rest = &data[..];
+            for n in split {
+                let n = n % (rest.len() + 1);
+                writer.write_all(&rest[..n]).unwrap();
+                rest = &rest[n..];
+            }
+            writer.write_all(rest).unwrap();
+            writer.finish().unwrap() == encode(Rfc4648 { padding: true }, &data).as_bytes()
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, Vec<usize>) -> bool)
+    }
+
+    #[test]
+    fn writer_with_config() {
+        let config = Config::new(Crockford).padding(true);
+        let mut writer = EncodeWriter::with_config(config, Vec::new());
+        writer.write_all(&[0xF8, 0x3E]).unwrap();
+        assert_eq!(writer.finish().unwrap(), b"Z0Z0====");
+    }
+
+    #[test]
+    fn writer_trailing_newline() {
+        let config = Config::new(Rfc4648 { padding: true }).trailing_newline(true);
+        let mut writer = EncodeWriter::with_config(config, Vec::new());
+        writer.write_all(b"foobar").unwrap();
+        assert_eq!(writer.finish().unwrap(), b"MZXW6YTBOI======\n");
+    }
+
+    #[test]
+    fn writer_drop_writes_tail() {
+        let mut out = Vec::new();
+        {
+            let mut writer = EncodeWriter::new(Rfc4648 { padding: true }, &mut out);
+            writer.write_all(b"foob").unwrap();
+        }
+        assert_eq!(out, b"MZXW6YQ=");
+    }
+
+    #[test]
+    fn writer_tail_written_once() {
+        /// A writer that fails its first write and records the rest.
+        struct FailFirst(Rc<RefCell<Vec<u8>>>, bool);
+
+        impl Write for FailFirst {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if !self.1 {
+                    self.1 = true;
+                    return Err(io::Error::other("first write"));
+                }
+                self.0.borrow_mut().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let config = Config::new(Rfc4648 { padding: true }).trailing_newline(true);
+        let out = Rc::new(RefCell::new(Vec::new()));
+        let mut writer = EncodeWriter::with_config(config, FailFirst(out.clone(), false));
+        writer.write_all(b"foo").unwrap();
+        assert!(writer.finish().is_err());
+        assert_eq!(*out.borrow(), b"");
+    }
+
+    #[test]
+    fn writer_progress() {
+        // The callback borrows `calls` rather than owning it.
+        let calls = Mutex::new(Vec::new());
+        let mut writer = EncodeWriter::new(Crockford, Vec::new())
+            .on_progress(|processed| calls.lock().unwrap().push(processed));
+        writer.write_all(&[0; 3]).unwrap();
+        writer.write_all(&[0; 3]).unwrap();
+        writer.write_all(&[0; 12000]).unwrap();
+        writer.write_all(&[0; 2]).unwrap();
+        assert_eq!(*calls.lock().unwrap(), [5, 10, 5130, 10250, 12005]);
+        writer.finish().unwrap();
+        assert_eq!(*calls.lock().unwrap(), [5, 10, 5130, 10250, 12005, 12008]);
+    }
+
+    #[test]
+    fn writer_observer() {
+        fn test(data: Vec<u8>, split: Vec<usize>) -> bool {
+            let seen = Mutex::new(Vec::new());
+            let mut writer = EncodeWriter::new(Crockford, Vec::new())
+                .with_observer(|input| seen.lock().unwrap().extend_from_slice(input));
+            let mut rest = &data[..];
+            for n in split {
+                let n = n % (rest.len() + 1);
+                writer.write_all(&rest[..n]).unwrap();
+                rest = &rest[n..];
+                if writer.total_in() != data.len() - rest.len() {
+                    return false;
+                }
+                // Only whole groups have been encoded so far.
+                if seen.lock().unwrap().len() != writer.total_in() / 5 * 5 {
+                    return false;
+                }
+            }
+            writer.write_all(rest).unwrap();
+            writer.finish().unwrap();
+            let seen = seen.into_inner().unwrap();
+            seen == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, Vec<usize>) -> bool);
+    }
+
+    #[test]
+    fn writer_is_send() {
+        fn is_send<T: Send>(_: &T) {}
+        let progress = Mutex::new(0);
+        let seen = Mutex::new(Vec::new());
+        let writer = EncodeWriter::new(Crockford, Vec::new())
+            .on_progress(|processed| *progress.lock().unwrap() = processed)
+            .with_observer(|input| seen.lock().unwrap().extend_from_slice(input));
+        is_send(&writer);
+        let out = std::thread::scope(|scope| {
+            scope
+                .spawn(move || {
+                    let mut writer = writer;
+                    writer.write_all(b"foobar").unwrap();
+                    writer.finish().unwrap()
+                })
+                .join()
+                .unwrap()
+        });
+        assert_eq!(out, b"CSQPYRK1E8");
+        assert_eq!(*progress.lock().unwrap(), 6);
+        assert_eq!(*seen.lock().unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn decode_writer_matches_decode() {
+        fn test(data: Vec<u8>, split: Vec<usize>, padding: bool) -> bool {
+            let alphabet = Rfc4648 { padding };
+            let encoded = encode(alphabet, &data);
+            let mut writer = DecodeWriter::new(alphabet, Vec::new());
+            let mut rest = encoded.as_bytes();
+            for n in split {
+                let n = n % (rest.len() + 1);
+                writer.write_all(&rest[..n]).unwrap();
+                rest = &rest[n..];
+            }
+            writer.write_all(rest).unwrap();
+            writer.finish().unwrap() == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, Vec<usize>, bool) -> bool)
+    }
+
+    #[test]
+    fn decode_writer_is_eager() {
+        let data = (0..=255).cycle().take(103).collect::<Vec<u8>>();
+        let encoded = encode(Rfc4648 { padding: true }, &data);
+        let mut writer = DecodeWriter::new(Rfc4648 { padding: true }, Vec::new());
+        for (i, &c) in encoded.as_bytes().iter().enumerate() {
+            writer.write_all(&[c]).unwrap();
+            // Every complete group is through, except the last one, which is padded.
+            let groups = ((i + 1) / 8).min(data.len() / 5);
+            assert_eq!(writer.get_ref()[..], data[..groups * 5], "{}", i);
+        }
+        assert_eq!(writer.finish().unwrap(), data);
+    }
+
+    #[test]
+    fn decode_writer_invalid() {
+        let mut writer = DecodeWriter::new(Crockford, Vec::new());
+        writer.write_all(b"CSQPYRK1").unwrap();
+        let err = writer.write_all(b"E8CSQPYRKU000000").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::InvalidChar {
+                index: 17,
+                byte: b'U'
+            })
+        );
+        // The group before the invalid one was still written.
+        assert_eq!(
+            *writer.get_ref(),
+            decode(Crockford, "CSQPYRK1E8CSQPYR").unwrap()
+        );
+
+        let writer = {
+            let mut writer = DecodeWriter::new(Rfc4648 { padding: true }, Vec::new());
+            writer.write_all(b"MZXW6YTBO!").unwrap();
+            writer
+        };
+        let err = writer.finish().unwrap_err();
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::InvalidChar {
+                index: 9,
+                byte: b'!'
+            })
+        );
+    }
+
+    #[test]
+    fn from_reader() {
+        let data = (0..=255).cycle().take(20000).collect::<Vec<u8>>();
+        assert_eq!(
+            encode_from_reader(Crockford, &data[..]).unwrap(),
+            encode(Crockford, &data)
+        );
+        assert_eq!(encode_from_reader(Crockford, &[][..]).unwrap(), "");
+    }
+
+    #[test]
+    fn copy_through_readers() {
+        fn test(data: Vec<u8>, limit: usize, padding: bool) -> bool {
+            let limit = limit % 20 + 1;
+            let config = Config::new(Rfc4648 { padding }).trailing_newline(padding);
+            let input = Trickle { data: &data, limit };
+            let mut encoded = Vec::new();
+            io::copy(&mut EncodeReader::with_config(config, input), &mut encoded).unwrap();
+
+            let text = &encoded[..encoded.len() - usize::from(padding)];
+            let input = Trickle { data: text, limit };
+            let mut decoded = Vec::new();
+            io::copy(&mut DecodeReader::new(Rfc4648 { padding }, input), &mut decoded).unwrap();
+            encoded == config.encode(&data).as_bytes() && decoded == data
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize, bool) -> bool);
+    }
+
+    #[test]
+    fn copy_through_writers() {
+        let data = (0..=255).cycle().take(20003).collect::<Vec<u8>>();
+        let mut writer = EncodeWriter::new(Rfc4648 { padding: true }, Vec::new());
+        io::copy(&mut &data[..], &mut writer).unwrap();
+        let encoded = writer.finish().unwrap();
+        assert_eq!(encoded, encode(Rfc4648 { padding: true }, &data).as_bytes());
+
+        let mut writer = DecodeWriter::new(Rfc4648 { padding: true }, Vec::new());
+        io::copy(&mut &encoded[..], &mut writer).unwrap();
+        assert_eq!(writer.finish().unwrap(), data);
+    }
+
+    #[test]
+    fn decode_reader_invalid() {
+        let mut encoded = encode(Crockford, &[0; 1000]).into_bytes();
+        encoded[1500] = b'U';
+        let mut reader = DecodeReader::new(Crockford, &encoded[..]);
+        let mut out = Vec::new();
+        let err = reader.read_to_end(&mut out).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::InvalidChar {
+                index: 1500,
+                byte: b'U'
+            })
+        );
+        // Every group before the invalid one was read first.
+        assert_eq!(out, [0; 1500 / 8 * 5]);
+        assert_eq!(reader.read(&mut [0; 16]).unwrap(), 0);
+    }
+
+    #[test]
+    fn transcode_matches_decode_encode() {
+        fn test(data: Vec<u8>, limit: usize) -> bool {
+            let encoded = encode(Rfc4648 { padding: true }, &data);
+            let input = Trickle {
+                data: encoded.as_bytes(),
+                limit: limit % 20 + 1,
+            };
+            let mut out = Vec::new();
+            transcode(Rfc4648 { padding: true }, Crockford, input, &mut out).unwrap();
+            out == encode(Crockford, &data).as_bytes()
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>, usize) -> bool);
+
+        let data = (0..=255).cycle().take(5000).collect::<Vec<u8>>();
+        let encoded = encode(Crockford, &data);
+        let mut out = Vec::new();
+        transcode(
+            Crockford,
+            Rfc4648 { padding: true },
+            encoded.as_bytes(),
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(out, encode(Rfc4648 { padding: true }, &data).as_bytes());
+    }
+
+    #[test]
+    fn transcode_invalid() {
+        let mut encoded = encode(Crockford, &[0; 1000]);
+        encoded.insert(1500, 'U');
+        let mut out = Vec::new();
+        let err = transcode(
+            Crockford,
+            Rfc4648 { padding: true },
+            encoded.as_bytes(),
+            &mut out,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let inner = err.get_ref().unwrap().downcast_ref::<DecodeError>();
+        assert_eq!(
+            inner,
+            Some(&DecodeError::InvalidChar {
+                index: 1500,
+                byte: b'U'
+            })
+        );
+
+        let err = transcode(
+            Crockford,
+            Rfc4648 { padding: true },
+            &b"CSQPYRK1EU"[..],
+            &mut out,
+        )
+        .unwrap_err();
+        let inner = err.get_ref().unwrap().downcast_ref::<DecodeError>();
+        assert_eq!(
+            inner,
+            Some(&DecodeError::InvalidChar {
+                index: 9,
+                byte: b'U'
+            })
+        );
+    }
+
+    #[test]
+    fn to_writer_matches_decode() {
+        fn test(data: Vec<u8>) -> bool {
+            let encoded = encode(Crockford, &data);
+            let mut out = Vec::new();
+            let written = decode_to_writer(Crockford, &encoded, &mut out).unwrap();
+            written == data.len() && Some(out) == decode(Crockford, &encoded)
+        }
+        quickcheck::quickcheck(test as fn(Vec<u8>) -> bool);
+
+        let data = (0..=255).cycle().take(5000).collect::<Vec<u8>>();
+        let encoded = encode(Rfc4648 { padding: true }, &data);
+        let mut out = Vec::new();
+        decode_to_writer(Rfc4648 { padding: true }, &encoded, &mut out).unwrap();
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn to_writer_invalid() {
+        let mut encoded = encode(Crockford, &[0; 1000]);
+        encoded.push('U');
+        let mut out = Vec::new();
+        let err = decode_to_writer(Crockford, &encoded, &mut out).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let inner = err.get_ref().unwrap().downcast_ref::<DecodeError>();
+        assert_eq!(
+            inner,
+            Some(&DecodeError::InvalidChar {
+                index: 1600,
+                byte: b'U'
+            })
+        );
+    }
+}